@@ -1 +1 @@
-aoc2024::benchmark! { day1 }
+aoc2024::benchmark! { day1::Day1: part1_radix, part2_counting }
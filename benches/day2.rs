@@ -1 +1 @@
-aoc2024::benchmark! { day2 }
+aoc2024::benchmark! { day2::Day2 }
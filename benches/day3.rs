@@ -1 +1 @@
-aoc2024::benchmark! { day3 }
+aoc2024::benchmark! { day3::Day3 }
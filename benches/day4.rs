@@ -1 +1 @@
-aoc2024::benchmark! { day4 }
+aoc2024::benchmark! { day4::Day4 }
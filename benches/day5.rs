@@ -1 +1 @@
-aoc2024::benchmark! { day5 }
+aoc2024::benchmark! { day5::Day5 }
@@ -1 +1 @@
-aoc2024::benchmark! { day6 }
+aoc2024::benchmark! { day6::Day6 }
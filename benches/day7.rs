@@ -1 +1 @@
-aoc2024::benchmark! { day7 }
+aoc2024::benchmark! { day7::Day7 }
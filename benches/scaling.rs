@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use aoc2024::{
+    generate::{self, Rng},
+    SOLUTIONS,
+};
+use criterion::{
+    criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode, Throughput,
+};
+
+#[global_allocator]
+static GLOBAL: aoc2024::allocator::Allocator = aoc2024::allocator::ALLOCATOR;
+
+const FACTORS: [usize; 4] = [1, 10, 100, 1000];
+
+/// Part 2 of day6 takes seconds per iteration at 1000x, and of day7 at 100x.
+fn max_factor(day: u8) -> usize {
+    match day {
+        6 => 100,
+        7 => 10,
+        _ => 1000,
+    }
+}
+
+/// Generates an input `factor` times the size of the puzzle input, with
+/// `factor` times the lines, or the area for grids.
+fn input(day: u8, factor: usize) -> String {
+    let rng = &mut Rng::new(u64::from(day));
+    let side = |base: usize| (base as f64 * (factor as f64).sqrt()).round() as usize;
+    match day {
+        1 => generate::day1(rng, 1000 * factor),
+        2 => generate::day2(rng, 1000 * factor),
+        3 => generate::day3(rng, 20000 * factor),
+        4 => generate::day4(rng, side(140), side(140)),
+        5 => generate::day5(rng, 200 * factor),
+        6 => generate::day6(rng, side(130), side(130)),
+        7 => generate::day7(rng, 850 * factor).input,
+        _ => panic!("no generator for day {day}"),
+    }
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    for solution in SOLUTIONS {
+        let day = solution.day();
+
+        let mut group = c.benchmark_group(format!("day{day} scaling"));
+        group
+            .sample_size(10)
+            .sampling_mode(SamplingMode::Flat)
+            .warm_up_time(Duration::from_millis(100))
+            .measurement_time(Duration::from_secs(2))
+            .nresamples(10_000);
+        for factor in FACTORS.into_iter().filter(|&f| f <= max_factor(day)) {
+            let input = input(day, factor);
+            let input = input.as_str();
+            group.throughput(Throughput::Bytes(input.len() as u64));
+            for part in [1, 2] {
+                group.bench_with_input(
+                    BenchmarkId::new(format!("part {part}"), format!("{factor}x")),
+                    input,
+                    |b, input| b.iter(|| solution.part(part, &solution.parse(input).unwrap())),
+                );
+            }
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
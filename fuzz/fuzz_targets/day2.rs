@@ -0,0 +1,58 @@
+#![no_main]
+
+use aoc2024::day2;
+use libfuzzer_sys::fuzz_target;
+
+fn is_safe(levels: &[i64]) -> bool {
+    match levels {
+        [] => false,
+        [_] => true,
+        _ => {
+            let increasing = levels[1] > levels[0];
+            levels.windows(2).all(|pair| {
+                let delta = if increasing {
+                    pair[1] - pair[0]
+                } else {
+                    pair[0] - pair[1]
+                };
+                (1..=3).contains(&delta)
+            })
+        }
+    }
+}
+
+fn is_safe_dampened(levels: &[i64]) -> bool {
+    is_safe(levels)
+        || (0..levels.len()).any(|skip| {
+            let mut levels = levels.to_vec();
+            levels.remove(skip);
+            is_safe(&levels)
+        })
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(parsed) = day2::parse(input) else {
+        return;
+    };
+
+    let reports: Vec<Vec<i64>> = input
+        .split('\n')
+        .map(|line| {
+            line.split(' ')
+                .filter(|level| !level.is_empty())
+                .map(|level| level.parse().unwrap())
+                .collect()
+        })
+        .collect();
+    let part1 = reports.iter().filter(|report| is_safe(report)).count() as u64;
+    let part2 = reports
+        .iter()
+        .filter(|report| is_safe_dampened(report))
+        .count() as u64;
+
+    assert_eq!(day2::solve_part1(&parsed), part1);
+    assert_eq!(day2::solve_part2(&parsed), part2);
+});
@@ -0,0 +1,19 @@
+#![no_main]
+
+use std::str::FromStr;
+
+use aoc2024::day5;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let parsed = day5::Input::from_str(input);
+    assert_eq!(parsed, day5::parse_scalar(input));
+
+    if let Ok(parsed) = parsed {
+        day5::solve_part1(&parsed);
+        let _ = day5::solve_part2(&parsed);
+    }
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use aoc2024::day7;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let parsed = day7::parse(input);
+    let scalar = day7::parse_scalar(input);
+    match (&parsed, &scalar) {
+        (Ok(parsed), Ok(scalar)) => assert_eq!(parsed, scalar),
+        (Err(_), Err(_)) => {}
+        _ => panic!("fast path gave {parsed:?}, scalar gave {scalar:?}"),
+    }
+});
@@ -0,0 +1,33 @@
+//! The allocator picked by cargo features, for binaries to install with
+//!
+//! ```
+//! #[global_allocator]
+//! static GLOBAL: aoc2024::allocator::Allocator = aoc2024::allocator::ALLOCATOR;
+//! # fn main() {}
+//! ```
+
+#[cfg(any(
+    feature = "system",
+    not(any(feature = "jemalloc", feature = "mimalloc"))
+))]
+mod selected {
+    pub type Allocator = std::alloc::System;
+    pub const ALLOCATOR: Allocator = std::alloc::System;
+}
+
+#[cfg(all(feature = "mimalloc", not(feature = "system")))]
+mod selected {
+    pub type Allocator = mimalloc::MiMalloc;
+    pub const ALLOCATOR: Allocator = mimalloc::MiMalloc;
+}
+
+#[cfg(all(
+    feature = "jemalloc",
+    not(any(feature = "system", feature = "mimalloc"))
+))]
+mod selected {
+    pub type Allocator = jemallocator::Jemalloc;
+    pub const ALLOCATOR: Allocator = jemallocator::Jemalloc;
+}
+
+pub use selected::{Allocator, ALLOCATOR};
@@ -0,0 +1,111 @@
+//! Known-correct answers, kept in a TOML file with a table per day:
+//!
+//! ```toml
+//! [day1]
+//! part1 = 2285373
+//! part2 = "21142653"
+//! ```
+//!
+//! Answers may be integers or strings, and either part may be left out.
+
+use std::{collections::BTreeMap, fmt};
+
+/// Where the answers for the inputs in `input/2024` live.
+pub const PATH: &str = "input/2024/answers.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnswerFile {
+    answers: BTreeMap<(u8, u8), String>,
+}
+
+#[derive(Debug)]
+pub enum AnswerFileError {
+    Toml(toml::de::Error),
+    /// A key or value that is not a `dayN` table of `part1`/`part2` answers.
+    Entry {
+        key: String,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for AnswerFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnswerFileError::Toml(e) => write!(f, "{e}"),
+            AnswerFileError::Entry { key, expected } => {
+                write!(f, "`{key}`: expected {expected}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnswerFileError {}
+
+/// How an answer compares with the known one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl AnswerFile {
+    pub fn parse(s: &str) -> Result<Self, AnswerFileError> {
+        let table: toml::Table = s.parse().map_err(AnswerFileError::Toml)?;
+        let mut answers = BTreeMap::new();
+        for (key, value) in table {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|day| day.parse::<u8>().ok())
+                .ok_or_else(|| AnswerFileError::Entry {
+                    key: key.clone(),
+                    expected: "a `dayN` table",
+                })?;
+            let toml::Value::Table(parts) = value else {
+                return Err(AnswerFileError::Entry {
+                    key,
+                    expected: "a table of `part1` and `part2`",
+                });
+            };
+            for (part, answer) in parts {
+                let key = format!("{key}.{part}");
+                let part = match part.as_str() {
+                    "part1" => 1,
+                    "part2" => 2,
+                    _ => {
+                        return Err(AnswerFileError::Entry {
+                            key,
+                            expected: "`part1` or `part2`",
+                        })
+                    }
+                };
+                let answer = match answer {
+                    toml::Value::String(answer) => answer,
+                    toml::Value::Integer(answer) => answer.to_string(),
+                    _ => {
+                        return Err(AnswerFileError::Entry {
+                            key,
+                            expected: "an integer or string answer",
+                        })
+                    }
+                };
+                answers.insert((day, part), answer);
+            }
+        }
+        Ok(AnswerFile { answers })
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Missing,
+        }
+    }
+}
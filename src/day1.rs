@@ -1,92 +1,466 @@
-use std::cmp::Ordering;
+use nalgebra::DMatrix;
 
 use aoc_runner_derive::aoc;
 
+use crate::{
+    validate::{self, Finding},
+    Error, Solution,
+};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    const NAME: &'static str = "Historian Hysteria";
+
+    type Input = Input;
+    type Answer1 = i64;
+    type Answer2 = i64;
+    type Scratch = Input;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse(input)
+    }
+
+    fn validate(input: &str) -> Vec<Finding> {
+        validate(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<i64, Error> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<i64, Error> {
+        solve_part2(input)
+    }
+
+    fn part1_with(input: &str, scratch: &mut Input) -> Result<i64, Error> {
+        parse_into(input, scratch)?;
+        scratch.list1.sort_unstable();
+        scratch.list2.sort_unstable();
+        distance_of_sorted(&scratch.list1, &scratch.list2)
+    }
+
+    fn part2_with(input: &str, scratch: &mut Input) -> Result<i64, Error> {
+        parse_into(input, scratch)?;
+        scratch.list1.sort_unstable();
+        scratch.list2.sort_unstable();
+        similarity_of_sorted(&scratch.list1, &scratch.list2)
+    }
+
+    fn solve_with(input: &str, scratch: &mut Input) -> Result<(i64, i64), Error> {
+        parse_into(input, scratch)?;
+        scratch.list1.sort_unstable();
+        scratch.list2.sort_unstable();
+        Ok((
+            distance_of_sorted(&scratch.list1, &scratch.list2)?,
+            similarity_of_sorted(&scratch.list1, &scratch.list2)?,
+        ))
+    }
+}
+
 #[aoc(day1, part1)]
 pub fn part1(input: &str) -> i64 {
-    let (mut list1, mut list2) = parse_input(input.as_bytes());
+    try_part1(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+#[aoc(day1, part2)]
+pub fn part2(input: &str) -> i64 {
+    try_part2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_part1(input: &str) -> Result<i64, Error> {
+    solve_part1(&parse(input)?)
+}
+
+pub fn try_part2(input: &str) -> Result<i64, Error> {
+    solve_part2(&parse(input)?)
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Input {
+    list1: Vec<i64>,
+    list2: Vec<i64>,
+}
+
+pub fn parse(input: &str) -> Result<Input, Error> {
+    let (list1, list2) = parse_input(input.as_bytes())?;
+    Ok(Input { list1, list2 })
+}
+
+/// Like [`parse`], but reuses the lists in `parsed`. Only input in the
+/// puzzle's own format is parsed without allocating.
+pub fn parse_into(input: &str, parsed: &mut Input) -> Result<(), Error> {
+    let Input { list1, list2 } = parsed;
+    if parse_fixed_width(input.as_bytes(), list1, list2).is_none() {
+        *parsed = parse(input)?;
+    }
+    Ok(())
+}
+
+/// Reports ids that are not integers, lines with a different number of ids
+/// than the first, and lines that are not exactly `NNNNN   NNNNN`.
+pub fn validate(input: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut columns = None;
+    for (line, text) in validate::lines(input) {
+        // The fixed-width path gives up on the first of these lines, but each
+        // is reported so that they can all be fixed at once.
+        if let Some(column) = fixed_width_mismatch(text) {
+            findings.push(Finding::slow_path(
+                line,
+                column,
+                "line is not `NNNNN   NNNNN`, which the fixed-width path needs",
+            ));
+        }
+
+        let mut count = 0;
+        for (start, id) in text
+            .split(u8::is_ascii_whitespace)
+            .scan(0, |start, id| {
+                let id_start = *start;
+                *start += id.len() + 1;
+                Some((id_start, id))
+            })
+            .filter(|(_, id)| !id.is_empty())
+        {
+            if std::str::from_utf8(id)
+                .ok()
+                .and_then(|id| id.parse::<i64>().ok())
+                .is_none()
+            {
+                findings.push(Finding::malformed(line, start + 1, "expected location id"));
+            }
+            count += 1;
+        }
+        match columns {
+            _ if count == 0 => {}
+            None => columns = Some((line, count)),
+            Some((_, expected)) if count != expected => findings.push(Finding::malformed(
+                line,
+                1,
+                format!("{count} location ids, but the first line has {expected}"),
+            )),
+            Some(_) => {}
+        }
+    }
+    if let Some((line, count)) = columns.filter(|&(_, count)| count != 2) {
+        findings.push(Finding::malformed(
+            line,
+            1,
+            format!("expected 2 columns of location ids, found {count}"),
+        ));
+    }
+    findings.sort_by_key(|finding| (finding.line, finding.column));
+    findings
+}
+
+/// The column where `text` stops matching `NNNNN   NNNNN`, if it does.
+fn fixed_width_mismatch(text: &[u8]) -> Option<usize> {
+    (0..14).find_map(|i| {
+        let fits = match (i, text.get(i)) {
+            (13, c) => c.is_none(),
+            (5..8, Some(&c)) => c == b' ',
+            (_, Some(c)) => c.is_ascii_digit(),
+            (_, None) => false,
+        };
+        (!fits).then_some(i + 1)
+    })
+}
+
+pub fn solve_part1(input: &Input) -> Result<i64, Error> {
+    let mut list1 = input.list1.clone();
+    let mut list2 = input.list2.clone();
     list1.sort_unstable();
     list2.sort_unstable();
-    list1
-        .into_iter()
+    distance_of_sorted(&list1, &list2)
+}
+
+pub fn solve_part2(input: &Input) -> Result<i64, Error> {
+    similarity_by_sorting(input.list1.clone(), input.list2.clone())
+}
+
+/// Parses any number of whitespace separated columns of location ids, which
+/// must all be the same length. An input without ids has no columns.
+pub fn parse_columns(input: &str) -> Result<Vec<Vec<i64>>, Error> {
+    let (mut list1, mut list2) = (Vec::new(), Vec::new());
+    let columns = match parse_fixed_width(input.as_bytes(), &mut list1, &mut list2) {
+        Some(()) if list1.is_empty() => Vec::new(),
+        Some(()) => vec![list1, list2],
+        None => parse_any_width(input.as_bytes())?,
+    };
+    check_lengths(&columns)?;
+    Ok(columns)
+}
+
+/// Columns of different lengths have no pairing of ids to compare.
+fn check_lengths(columns: &[Vec<i64>]) -> Result<(), Error> {
+    let Some(first) = columns.first() else {
+        return Ok(());
+    };
+    match columns
+        .iter()
+        .position(|column| column.len() != first.len())
+    {
+        Some(j) => Err(Error::Constraint(format!(
+            "column {} has {} location ids, but column 1 has {}",
+            j + 1,
+            columns[j].len(),
+            first.len()
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Entry `(i, j)` is the total distance between columns `i` and `j` once both
+/// are sorted, so `part1` is entry `(0, 1)` of a two column input.
+pub fn distance_matrix(columns: &[Vec<i64>]) -> Result<DMatrix<i64>, Error> {
+    matrix(columns, distance_of_sorted)
+}
+
+/// Entry `(i, j)` is the similarity score of column `i` against column `j`, so
+/// `part2` is entry `(0, 1)` of a two column input.
+pub fn similarity_matrix(columns: &[Vec<i64>]) -> Result<DMatrix<i64>, Error> {
+    matrix(columns, similarity_of_sorted)
+}
+
+/// `score` of every pair of columns, once each is sorted. Fails unless the
+/// columns are all the same length.
+fn matrix(
+    columns: &[Vec<i64>],
+    score: fn(&[i64], &[i64]) -> Result<i64, Error>,
+) -> Result<DMatrix<i64>, Error> {
+    check_lengths(columns)?;
+    let sorted: Vec<Vec<i64>> = columns
+        .iter()
+        .map(|column| {
+            let mut column = column.clone();
+            column.sort_unstable();
+            column
+        })
+        .collect();
+    let mut entries = Vec::with_capacity(sorted.len() * sorted.len());
+    for j in &sorted {
+        for i in &sorted {
+            entries.push(score(i, j)?);
+        }
+    }
+    Ok(DMatrix::from_vec(sorted.len(), sorted.len(), entries))
+}
+
+fn distance_of_sorted(list1: &[i64], list2: &[i64]) -> Result<i64, Error> {
+    let total = list1
+        .iter()
         .zip(list2)
-        .map(|(l, r)| (l - r).abs())
-        .sum()
+        .map(|(l, r)| l.abs_diff(*r) as i128)
+        .sum();
+    answer("total distance", total)
 }
 
-#[aoc(day1, part2)]
-pub fn part2(input: &str) -> i64 {
-    let (mut list1, mut list2) = parse_input(input.as_bytes());
-    list1.sort_unstable_by(|a, b| a.cmp(b).reverse());
-    list2.sort_unstable_by(|a, b| a.cmp(b).reverse());
+/// Narrows a total summed in `i128`, where it cannot overflow, to an answer.
+fn answer(name: &str, total: i128) -> Result<i64, Error> {
+    i64::try_from(total).map_err(|_| Error::Constraint(format!("{name} {total} overflows")))
+}
+
+#[aoc(day1, part1, radix)]
+pub fn part1_radix(input: &str) -> i64 {
+    try_part1_radix(input).unwrap_or_else(|e| panic!("{e}"))
+}
 
-    let mut prev_score = 0;
-    let mut prev_value = -1;
+/// [`try_part1`], sorting the lists with a radix sort.
+pub fn try_part1_radix(input: &str) -> Result<i64, Error> {
+    let (mut list1, mut list2) = parse_input(input.as_bytes())?;
+    radix_sort(&mut list1);
+    radix_sort(&mut list2);
+    distance_of_sorted(&list1, &list2)
+}
+
+/// Ids spanning more values than this are scored by sorting instead, as the
+/// counting table would no longer fit in cache.
+const MAX_COUNTING_RANGE: u64 = 1 << 20;
+
+#[aoc(day1, part2, counting)]
+pub fn part2_counting(input: &str) -> i64 {
+    try_part2_counting(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// [`try_part2`], counting the right list's ids in a table indexed by id.
+pub fn try_part2_counting(input: &str) -> Result<i64, Error> {
+    let (list1, list2) = parse_input(input.as_bytes())?;
+    let (Some(&min), Some(&max)) = (list2.iter().min(), list2.iter().max()) else {
+        return Ok(0);
+    };
+    if max.abs_diff(min) >= MAX_COUNTING_RANGE {
+        return similarity_by_sorting(list1, list2);
+    }
+
+    let mut counts = vec![0u32; (max - min) as usize + 1];
+    for r in list2 {
+        counts[(r - min) as usize] += 1;
+    }
+
+    let score = list1
+        .into_iter()
+        .filter(|l| (min..=max).contains(l))
+        .map(|l| l as i128 * counts[(l - min) as usize] as i128)
+        .sum();
+    answer("similarity score", score)
+}
+
+fn similarity_by_sorting(mut list1: Vec<i64>, mut list2: Vec<i64>) -> Result<i64, Error> {
+    list1.sort_unstable();
+    list2.sort_unstable();
+    similarity_of_sorted(&list1, &list2)
+}
+
+fn similarity_of_sorted(list1: &[i64], list2: &[i64]) -> Result<i64, Error> {
+    let mut prev_score = 0i128;
+    let mut prev_value = None;
     let mut score = 0;
+    let mut list2 = list2.iter().copied().peekable();
 
-    while let Some(n) = list1.pop() {
-        if prev_value == n {
+    for &n in list1 {
+        if prev_value == Some(n) {
             score += prev_score;
             continue;
         }
 
-        prev_value = n;
+        prev_value = Some(n);
         let mut prev_count = 0;
-        loop {
-            if let Some(v) = list2.pop() {
-                match v.cmp(&n) {
-                    Ordering::Less => {
-                        continue;
-                    }
-                    Ordering::Equal => {
-                        prev_count += 1;
-                    }
-                    Ordering::Greater => {
-                        list2.push(v);
-                        break;
-                    }
-                }
-            } else {
-                break;
+        while let Some(v) = list2.next_if(|&v| v <= n) {
+            if v == n {
+                prev_count += 1;
             }
         }
 
-        prev_score = n * prev_count;
+        prev_score = n as i128 * prev_count as i128;
         score += prev_score;
     }
 
-    score
+    answer("similarity score", score)
+}
+
+/// LSD radix sort a byte at a time, skipping bytes that all values share.
+fn radix_sort(values: &mut Vec<i64>) {
+    let key = |v: i64, byte: usize| (((v as u64) ^ (1 << 63)) >> (byte * 8)) as u8 as usize;
+
+    let mut counts = [[0usize; 256]; 8];
+    for &v in values.iter() {
+        for (byte, count) in counts.iter_mut().enumerate() {
+            count[key(v, byte)] += 1;
+        }
+    }
+
+    let mut sorted = vec![0; values.len()];
+    for (byte, count) in counts.iter_mut().enumerate() {
+        if count.contains(&values.len()) {
+            continue;
+        }
+
+        let mut offset = 0;
+        for c in count.iter_mut() {
+            (*c, offset) = (offset, offset + *c);
+        }
+        for &v in values.iter() {
+            let slot = &mut count[key(v, byte)];
+            sorted[*slot] = v;
+            *slot += 1;
+        }
+        std::mem::swap(values, &mut sorted);
+    }
 }
 
-fn parse_input(mut input: &[u8]) -> (Vec<i64>, Vec<i64>) {
+fn parse_input(input: &[u8]) -> Result<(Vec<i64>, Vec<i64>), Error> {
     let size = input.len() / 14 + 1;
     let mut list1 = Vec::<i64>::with_capacity(size);
     let mut list2 = Vec::<i64>::with_capacity(size);
+    if parse_fixed_width(input, &mut list1, &mut list2).is_some() {
+        return Ok((list1, list2));
+    }
+
+    match <[Vec<i64>; 2]>::try_from(parse_any_width(input)?) {
+        Ok([list1, list2]) => Ok((list1, list2)),
+        Err(columns) if columns.is_empty() => Ok((Vec::new(), Vec::new())),
+        Err(columns) => Err(Error::Constraint(format!(
+            "expected 2 columns of location ids, found {}",
+            columns.len()
+        ))),
+    }
+}
 
-    loop {
-        if input.len() < 12 {
-            break;
+/// Fast path for the puzzle's own format, where every line is exactly
+/// `NNNNN   NNNNN`. Gives up on anything else.
+fn parse_fixed_width(mut input: &[u8], list1: &mut Vec<i64>, list2: &mut Vec<i64>) -> Option<()> {
+    list1.clear();
+    list2.clear();
+
+    while !input.is_empty() {
+        let line = input.get(..13)?;
+        if &line[5..8] != b"   " {
+            return None;
+        }
+
+        list1.push(parse_5_digit_base_10(&line[..5])?);
+        list2.push(parse_5_digit_base_10(&line[8..13])?);
+
+        match input.get(13) {
+            None => break,
+            Some(b'\n') => input = &input[14..],
+            Some(_) => return None,
         }
-        let l = parse_5_digit_base_10(input[..5].try_into().unwrap());
-        let r = parse_5_digit_base_10(input[8..13].try_into().unwrap());
+    }
+
+    Some(())
+}
 
-        list1.push(l);
-        list2.push(r);
+fn parse_any_width(input: &[u8]) -> Result<Vec<Vec<i64>>, Error> {
+    let mut columns: Vec<Vec<i64>> = Vec::new();
 
-        if input.len() < 14 {
-            break;
+    for (i, line) in input.split(|&c| c == b'\n').enumerate() {
+        let first_line = columns.is_empty();
+        let mut count = 0;
+        let mut start = 0;
+        while start < line.len() {
+            if line[start].is_ascii_whitespace() {
+                start += 1;
+                continue;
+            }
+
+            let end = line[start..]
+                .iter()
+                .position(|c| c.is_ascii_whitespace())
+                .map_or(line.len(), |len| start + len);
+            let id = std::str::from_utf8(&line[start..end])
+                .ok()
+                .and_then(|id| id.parse::<i64>().ok())
+                .ok_or(Error::parse(i + 1, start + 1, "location id"))?;
+            if count == columns.len() {
+                if !first_line {
+                    return Err(Error::parse(i + 1, start + 1, "end of line"));
+                }
+                columns.push(Vec::new());
+            }
+            columns[count].push(id);
+            count += 1;
+            start = end;
+        }
+
+        if count != 0 && count != columns.len() {
+            return Err(Error::parse(i + 1, line.len() + 1, "location id"));
         }
-        input = &input[14..];
     }
 
-    (list1, list2)
+    Ok(columns)
 }
 
 #[inline(always)]
-fn parse_5_digit_base_10(input: [u8; 5]) -> i64 {
+fn parse_5_digit_base_10(input: &[u8]) -> Option<i64> {
     let mut n: i64 = 0;
-    for i in input {
+    for &i in input {
+        if !i.is_ascii_digit() {
+            return None;
+        }
         n = n * 10 + (i - b'0') as i64;
     }
-    n
+    Some(n)
 }
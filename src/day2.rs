@@ -1,15 +1,165 @@
 use std::simd::{
     cmp::{SimdPartialEq, SimdPartialOrd},
     i8x64,
-    num::{SimdInt as _, SimdUint as _},
+    num::SimdInt as _,
     u64x4, u8x8, Mask,
 };
 
 use aoc_runner_derive::aoc;
 
+use crate::{
+    validate::{self, Finding},
+    Error, Solution,
+};
+
+/// Each report is laid out in one `u8x8` lane.
+const MAX_LEVELS: u8 = 8;
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    const NAME: &'static str = "Red-Nosed Reports";
+
+    type Input = Input;
+    type Answer1 = u64;
+    type Answer2 = u64;
+    type Scratch = Scratch;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse(input)
+    }
+
+    fn validate(input: &str) -> Vec<Finding> {
+        validate(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u64, Error> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u64, Error> {
+        Ok(solve_part2(input))
+    }
+
+    fn part1_with(input: &str, scratch: &mut Scratch) -> Result<u64, Error> {
+        parse_into(input, &mut scratch.input)?;
+        Ok(solve_part1(&scratch.input))
+    }
+
+    fn part2_with(input: &str, scratch: &mut Scratch) -> Result<u64, Error> {
+        parse_into(input, &mut scratch.input)?;
+        Ok(count_dampened(&scratch.input, &mut scratch.buffers))
+    }
+
+    fn solve_with(input: &str, scratch: &mut Scratch) -> Result<(u64, u64), Error> {
+        parse_into(input, &mut scratch.input)?;
+        Ok((
+            solve_part1(&scratch.input),
+            count_dampened(&scratch.input, &mut scratch.buffers),
+        ))
+    }
+}
+
 #[aoc(day2, part1)]
 pub fn part1(input: &str) -> u64 {
-    let (data, num_levels) = parse_input(input);
+    try_part1(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+#[aoc(day2, part2)]
+pub fn part2(input: &str) -> u64 {
+    try_part2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_part1(input: &str) -> Result<u64, Error> {
+    Ok(solve_part1(&parse(input)?))
+}
+
+pub fn try_part2(input: &str) -> Result<u64, Error> {
+    Ok(solve_part2(&parse(input)?))
+}
+
+/// Reports padded to one `u8x8` lane each, with their level counts.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Input {
+    data: Vec<i8>,
+    num_levels: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+pub struct Scratch {
+    input: Input,
+    buffers: Buffers,
+}
+
+/// Everything part 2 allocates besides the input.
+#[derive(Debug, Default)]
+struct Buffers {
+    num_levels: Vec<u8>,
+    masks: Vec<u64>,
+    shifted: Vec<u64x4>,
+    readonly: Vec<u64x4>,
+    data: Vec<u64x4>,
+}
+
+pub fn parse(input: &str) -> Result<Input, Error> {
+    let mut parsed = Input {
+        data: Vec::with_capacity(4 * 1024 * 1024),
+        num_levels: Vec::with_capacity(1024 * 1024),
+    };
+    parse_into(input, &mut parsed)?;
+    Ok(parsed)
+}
+
+/// Like [`parse`], but reuses the buffers in `parsed`.
+pub fn parse_into(input: &str, parsed: &mut Input) -> Result<(), Error> {
+    parse_input(input, &mut parsed.data, &mut parsed.num_levels)
+}
+
+/// Reports the first bad level on every line: anything but 1 or 2 digits
+/// between spaces, or more than [`MAX_LEVELS`] levels.
+pub fn validate(input: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (line, text) in validate::lines(input) {
+        let mut levels = 0;
+        let mut start = 0;
+        for level in text.split(|&c| c == b' ') {
+            let column = start + 1;
+            start += level.len() + 1;
+            if level.is_empty() {
+                continue;
+            }
+            if let Some(offset) = level.iter().position(|c| !c.is_ascii_digit()) {
+                findings.push(Finding::malformed(line, column + offset, "expected level"));
+                break;
+            }
+            if level.len() > 2 {
+                findings.push(Finding::malformed(
+                    line,
+                    column + 2,
+                    "expected level of at most 2 digits",
+                ));
+                break;
+            }
+            levels += 1;
+            if levels > MAX_LEVELS {
+                findings.push(Finding::malformed(
+                    line,
+                    column,
+                    format!("more than {MAX_LEVELS} levels"),
+                ));
+                break;
+            }
+        }
+    }
+    findings
+}
+
+pub fn solve_part1(input: &Input) -> u64 {
+    if cfg!(target_arch = "wasm32") {
+        return solve_part1_scalar(input);
+    }
+    let Input { data, num_levels } = input;
 
     let mut result = 0;
     gen_num_safe_lines_masks(data.as_slice(), num_levels.as_slice(), |mask| {
@@ -19,9 +169,62 @@ pub fn part1(input: &str) -> u64 {
     result
 }
 
-#[aoc(day2, part2)]
-pub fn part2(input: &str) -> u64 {
-    let (orig_data, mut num_levels) = parse_input(input);
+pub fn solve_part2(input: &Input) -> u64 {
+    count_dampened(input, &mut Buffers::default())
+}
+
+/// [`solve_part1`] a report at a time, which wasm32 uses instead of 64 byte
+/// vectors, see `crate::wasm`.
+pub fn solve_part1_scalar(input: &Input) -> u64 {
+    reports(input).filter(|report| is_safe(report)).count() as u64
+}
+
+/// [`solve_part2`] a report at a time, like [`solve_part1_scalar`].
+pub fn solve_part2_scalar(input: &Input) -> u64 {
+    reports(input)
+        .filter(|report| {
+            is_safe(report)
+                || (0..report.len()).any(|i| {
+                    let mut dampened = [0; MAX_LEVELS as usize];
+                    dampened[..i].copy_from_slice(&report[..i]);
+                    dampened[i..(report.len() - 1)].copy_from_slice(&report[(i + 1)..]);
+                    is_safe(&dampened[..(report.len() - 1)])
+                })
+        })
+        .count() as u64
+}
+
+fn reports(input: &Input) -> impl Iterator<Item = &[i8]> {
+    input.num_levels.iter().enumerate().map(|(i, &levels)| {
+        let start = i * size_of::<u8x8>();
+        &input.data[start..(start + levels as usize)]
+    })
+}
+
+/// Empty reports are unsafe, like in the vectorized solvers.
+fn is_safe(report: &[i8]) -> bool {
+    !report.is_empty()
+        && (report.windows(2).all(|w| (1..=3).contains(&(w[1] - w[0])))
+            || report
+                .windows(2)
+                .all(|w| (-3..=-1).contains(&(w[1] - w[0]))))
+}
+
+fn count_dampened(input: &Input, buffers: &mut Buffers) -> u64 {
+    if cfg!(target_arch = "wasm32") {
+        return solve_part2_scalar(input);
+    }
+    let orig_data = &input.data;
+    let Buffers {
+        num_levels,
+        masks: all_masks,
+        shifted: data_shifted,
+        readonly: data_readonly,
+        data,
+    } = buffers;
+    num_levels.clear();
+    num_levels.extend_from_slice(&input.num_levels);
+    all_masks.clear();
 
     let one = u8x8::splat(1);
     let chunks = orig_data.len() / size_of::<i8x64>();
@@ -29,19 +232,18 @@ pub fn part2(input: &str) -> u64 {
         let l = c * size_of::<u8x8>();
         let range = l..(l + size_of::<u8x8>());
         let levels = u8x8::from_slice(&num_levels[range.clone()]);
-        num_levels[range].copy_from_slice(&levels.saturating_sub(one).to_array()[..]);
+        // A single level stays safe with nothing removed, while a count of 0
+        // would mark the report as empty.
+        let removed = levels.simd_gt(one).select(one, u8x8::splat(0));
+        num_levels[range].copy_from_slice(&(levels - removed).to_array()[..]);
     }
 
-    let mut all_masks = Vec::with_capacity(1024 * 1024);
-
-    let data_shifted = signed_bytes_to_u64x4(&orig_data[1..]);
-    let data_readonly = signed_bytes_to_u64x4(&orig_data[..(orig_data.len() - 1)]);
-
-    let mut data = Vec::with_capacity(data_readonly.len());
+    signed_bytes_to_u64x4(&orig_data[1..], data_shifted);
+    signed_bytes_to_u64x4(&orig_data[..(orig_data.len() - 1)], data_readonly);
 
     for i in 0..8 {
         data.clear();
-        data.extend_from_slice(&data_readonly);
+        data.extend_from_slice(data_readonly);
 
         let mut line_num = 0;
         for (old, new) in data.iter_mut().zip(data_shifted.iter()) {
@@ -58,7 +260,7 @@ pub fn part2(input: &str) -> u64 {
             line_num += 4;
         }
 
-        let new_data = u64x4_to_signed_bytes_with_trailing_zero(&mut data);
+        let new_data = u64x4_to_signed_bytes_with_trailing_zero(data);
 
         if i == 0 {
             gen_num_safe_lines_masks(new_data, num_levels.as_slice(), |mask| all_masks.push(mask));
@@ -72,26 +274,24 @@ pub fn part2(input: &str) -> u64 {
     }
 
     let mut result = 0;
-    for mask in all_masks {
+    for mask in all_masks.iter() {
         result += mask.count_ones() as u64;
     }
 
     result
 }
 
-fn signed_bytes_to_u64x4(bytes: &[i8]) -> Vec<u64x4> {
+fn signed_bytes_to_u64x4(bytes: &[i8], out: &mut Vec<u64x4>) {
     let bytes: &[u8] =
         unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const u8, bytes.len()) };
 
-    bytes
-        .array_chunks::<32>()
-        .map(|chunk| {
-            let arr: [u64; 4] = array_init::array_init(|i| {
-                u64::from_ne_bytes(chunk[(i * 8)..((i + 1) * 8)].try_into().unwrap())
-            });
-            u64x4::from_array(arr)
-        })
-        .collect()
+    out.clear();
+    out.extend(bytes.array_chunks::<32>().map(|chunk| {
+        let arr: [u64; 4] = array_init::array_init(|i| {
+            u64::from_ne_bytes(chunk[(i * 8)..((i + 1) * 8)].try_into().unwrap())
+        });
+        u64x4::from_array(arr)
+    }));
 }
 
 fn u64x4_to_signed_bytes_with_trailing_zero(vec: &mut Vec<u64x4>) -> &[i8] {
@@ -162,23 +362,34 @@ fn delta_mask_to_line_bitset(delta_mask: Mask<i8, 64>) -> u8x8 {
     u8x8::from_array(delta_mask.to_bitmask().to_ne_bytes())
 }
 
-fn parse_input(input: &str) -> (Vec<i8>, Vec<u8>) {
-    let mut input = input.as_bytes();
+fn parse_input(input: &str, data: &mut Vec<i8>, num_levels: &mut Vec<u8>) -> Result<(), Error> {
+    let all_input = input.as_bytes();
+    let mut input = all_input;
 
-    let mut data = Vec::<i8>::with_capacity(4 * 1024 * 1024);
-    let mut num_levels = Vec::<u8>::with_capacity(1024 * 1024);
+    data.clear();
+    num_levels.clear();
 
     let mut levels = 0;
+    let mut line = 1;
+    let mut line_start = 0;
     loop {
-        match (input.get(0), input.get(1)) {
+        let column = all_input.len() - input.len() - line_start + 1;
+        match (input.first(), input.get(1)) {
             (f @ Some(b'\n'), _) | (f @ None, _) => {
                 num_levels.push(levels);
+                // Empty reports need a lane of their own too, except for the
+                // one after a trailing newline.
+                if levels == 0 && f.is_some() {
+                    data.extend([0; size_of::<u8x8>()]);
+                }
                 levels = 0;
                 while (data.len() % size_of::<u8x8>()) != 0 {
                     data.push(0);
                 }
                 if f.is_some() {
                     input = &input[1..];
+                    line += 1;
+                    line_start = all_input.len() - input.len();
                 } else {
                     break;
                 }
@@ -186,20 +397,41 @@ fn parse_input(input: &str) -> (Vec<i8>, Vec<u8>) {
             (Some(b' '), _) => {
                 input = &input[1..];
             }
-            (Some(f), None | Some(b'\n') | Some(b' ')) => {
-                data.push((f - b'0') as i8);
-                levels += 1;
-                input = &input[1..];
-            }
-            (Some(f), Some(s)) => {
-                data.push((10 * (f - b'0') + (s - b'0')) as i8);
+            (Some(f), next) => {
+                if !f.is_ascii_digit() {
+                    return Err(Error::parse(line, column, "level"));
+                }
+                if levels == MAX_LEVELS {
+                    return Err(Error::Constraint(format!(
+                        "line {line} has more than {MAX_LEVELS} levels"
+                    )));
+                }
+
+                match next {
+                    None | Some(b'\n') | Some(b' ') => {
+                        data.push((f - b'0') as i8);
+                        input = &input[1..];
+                    }
+                    Some(s) if s.is_ascii_digit() => {
+                        if input.get(2).is_some_and(u8::is_ascii_digit) {
+                            return Err(Error::parse(
+                                line,
+                                column + 2,
+                                "level of at most 2 digits",
+                            ));
+                        }
+                        data.push((10 * (f - b'0') + (s - b'0')) as i8);
+                        input = &input[2..];
+                    }
+                    Some(_) => return Err(Error::parse(line, column + 1, "space or newline")),
+                }
                 levels += 1;
-                input = &input[2..];
             }
         }
     }
 
-    while (data.len() % size_of::<u64x4>()) != 0 {
+    // The solvers only look at whole `i8x64` chunks of eight reports.
+    while (data.len() % size_of::<i8x64>()) != 0 {
         data.push(0);
     }
 
@@ -209,5 +441,5 @@ fn parse_input(input: &str) -> (Vec<i8>, Vec<u8>) {
         num_levels.push(0);
     }
 
-    (data, num_levels)
+    Ok(())
 }
@@ -1,37 +1,160 @@
+use regex::bytes::{CaptureLocations, Regex};
+
 use aoc_runner_derive::aoc;
-use regex::bytes::Regex;
 
-use jemallocator::Jemalloc;
+use crate::{validate::Finding, Error, Solution};
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    const NAME: &'static str = "Mull It Over";
+
+    type Input = Vec<Instruction>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+    type Scratch = Scratch;
 
-#[global_allocator]
-static GLOBAL: Jemalloc = Jemalloc;
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse(input)
+    }
+
+    fn validate(input: &str) -> Vec<Finding> {
+        validate(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u64, Error> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u64, Error> {
+        Ok(solve_part2(input))
+    }
+
+    fn part1_with(input: &str, scratch: &mut Scratch) -> Result<u64, Error> {
+        parse_into(input, scratch);
+        Ok(solve_part1(&scratch.instructions))
+    }
+
+    fn part2_with(input: &str, scratch: &mut Scratch) -> Result<u64, Error> {
+        parse_into(input, scratch);
+        Ok(solve_part2(&scratch.instructions))
+    }
+
+    fn solve_with(input: &str, scratch: &mut Scratch) -> Result<(u64, u64), Error> {
+        parse_into(input, scratch);
+        Ok((
+            solve_part1(&scratch.instructions),
+            solve_part2(&scratch.instructions),
+        ))
+    }
+}
 
 #[aoc(day3, part1)]
 pub fn part1(input: &str) -> u64 {
-    let re = Regex::new(r"mul\(([0-9]+),([0-9]+)\)").unwrap();
+    try_part1(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+#[aoc(day3, part2)]
+pub fn part2(input: &str) -> u64 {
+    try_part2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_part1(input: &str) -> Result<u64, Error> {
+    Ok(solve_part1(&parse(input)?))
+}
+
+pub fn try_part2(input: &str) -> Result<u64, Error> {
+    Ok(solve_part2(&parse(input)?))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(u64, u64),
+    Do,
+    Dont,
+}
+
+/// The compiled regex along with the instructions it last found.
+#[derive(Debug)]
+pub struct Scratch {
+    regex: Regex,
+    locations: CaptureLocations,
+    instructions: Vec<Instruction>,
+}
+
+impl Default for Scratch {
+    fn default() -> Self {
+        // Operands are 1 to 3 digits, so `mul(1234,5)` is corrupted memory
+        // rather than an instruction.
+        let regex = Regex::new(r"mul\(([0-9]{1,3}),([0-9]{1,3})\)|(don't)\(\)|(do)\(\)").unwrap();
+        Scratch {
+            locations: regex.capture_locations(),
+            regex,
+            instructions: Vec::with_capacity(1024),
+        }
+    }
+}
+
+/// Never fails: corrupted memory is allowed to contain anything.
+pub fn parse(input: &str) -> Result<Vec<Instruction>, Error> {
+    let mut scratch = Scratch::default();
+    parse_into(input, &mut scratch);
+    Ok(scratch.instructions)
+}
+
+/// Like [`parse`], but reuses the regex and buffers in `scratch`.
+pub fn parse_into(input: &str, scratch: &mut Scratch) {
+    let Scratch {
+        regex,
+        locations,
+        instructions,
+    } = scratch;
+    let input = input.as_bytes();
+    let group = |locations: &CaptureLocations, i| locations.get(i).map(|(s, e)| &input[s..e]);
+
+    instructions.clear();
+    let mut start = 0;
+    while let Some(m) = regex.captures_read_at(locations, input, start) {
+        start = m.end();
+        if let Some(a) = group(locations, 1) {
+            let b = group(locations, 2).unwrap();
+            instructions.push(Instruction::Mul(parse_number(a), parse_number(b)));
+        } else if group(locations, 3).is_some() {
+            instructions.push(Instruction::Dont);
+        } else if group(locations, 4).is_some() {
+            instructions.push(Instruction::Do);
+        }
+    }
+}
+
+/// Corrupted memory is allowed to contain anything, so nothing is reported.
+pub fn validate(_: &str) -> Vec<Finding> {
+    Vec::new()
+}
+
+pub fn solve_part1(instructions: &[Instruction]) -> u64 {
     let mut total: u64 = 0;
-    for capture in re.captures_iter(input.as_bytes()) {
-        total += parse_number(capture.get(1).unwrap().as_bytes())
-            * parse_number(capture.get(2).unwrap().as_bytes());
+    for instruction in instructions {
+        if let Instruction::Mul(a, b) = instruction {
+            total += a * b;
+        }
     }
     total
 }
 
-#[aoc(day3, part2)]
-pub fn part2(input: &str) -> u64 {
-    let re = Regex::new(r"mul\(([0-9]+),([0-9]+)\)|(don't)\(\)|(do)\(\)").unwrap();
+pub fn solve_part2(instructions: &[Instruction]) -> u64 {
     let mut total: u64 = 0;
     let mut enabled = true;
-    for capture in re.captures_iter(input.as_bytes()) {
-        if let Some(c) = capture.get(1) {
-            if enabled {
-                total +=
-                    parse_number(c.as_bytes()) * parse_number(capture.get(2).unwrap().as_bytes());
+    for instruction in instructions {
+        match instruction {
+            Instruction::Mul(a, b) => {
+                if enabled {
+                    total += a * b;
+                }
             }
-        } else if capture.get(3).is_some() {
-            enabled = false;
-        } else if capture.get(4).is_some() {
-            enabled = true;
+            Instruction::Do => enabled = true,
+            Instruction::Dont => enabled = false,
         }
     }
     total
@@ -42,12 +165,6 @@ fn parse_number(text: &[u8]) -> u64 {
         3 => 100 * (text[0] - b'0') as u64 + 10 * (text[1] - b'0') as u64 + (text[2] - b'0') as u64,
         2 => 10 * (text[0] - b'0') as u64 + (text[1] - b'0') as u64,
         1 => (text[0] - b'0') as u64,
-        _ => {
-            let mut total = 0;
-            for c in text {
-                total = total * 10 + (c - b'0') as u64;
-            }
-            total
-        }
+        _ => unreachable!("the regex only matches 1 to 3 digits"),
     }
 }
@@ -1,8 +1,113 @@
 use aoc_runner_derive::aoc;
 
+use crate::{
+    validate::{self, Finding},
+    Error, Solution,
+};
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    const NAME: &'static str = "Ceres Search";
+
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = u64;
+    type Scratch = Input;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse(input)
+    }
+
+    fn validate(input: &str) -> Vec<Finding> {
+        validate(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize, Error> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u64, Error> {
+        Ok(solve_part2(input))
+    }
+
+    fn part1_with(input: &str, scratch: &mut Input) -> Result<usize, Error> {
+        parse_into(input, scratch)?;
+        Ok(solve_part1(scratch))
+    }
+
+    fn part2_with(input: &str, scratch: &mut Input) -> Result<u64, Error> {
+        parse_into(input, scratch)?;
+        Ok(solve_part2(scratch))
+    }
+
+    fn solve_with(input: &str, scratch: &mut Input) -> Result<(usize, u64), Error> {
+        parse_into(input, scratch)?;
+        Ok((solve_part1(scratch), solve_part2(scratch)))
+    }
+}
+
 #[aoc(day4, part1)]
 pub fn part1(input: &str) -> usize {
-    let (input, width, height) = parse_input(input);
+    try_part1(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+#[aoc(day4, part2)]
+pub fn part2(input: &str) -> u64 {
+    try_part2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_part1(input: &str) -> Result<usize, Error> {
+    Ok(solve_part1(&parse(input)?))
+}
+
+pub fn try_part2(input: &str) -> Result<u64, Error> {
+    Ok(solve_part2(&parse(input)?))
+}
+
+/// The word search, one row per `width` bytes including its newline.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Input {
+    grid: Vec<u8>,
+    width: usize,
+    height: usize,
+}
+
+pub fn parse(input: &str) -> Result<Input, Error> {
+    let mut parsed = Input::default();
+    parse_into(input, &mut parsed)?;
+    Ok(parsed)
+}
+
+/// Like [`parse`], but reuses the grid in `parsed`.
+pub fn parse_into(input: &str, parsed: &mut Input) -> Result<(), Error> {
+    (parsed.width, parsed.height) = parse_input(input, &mut parsed.grid)?;
+    Ok(())
+}
+
+/// Reports every row that is not as wide as the first.
+pub fn validate(input: &str) -> Vec<Finding> {
+    let mut lines = validate::lines(input);
+    let Some((_, first)) = lines.next() else {
+        return Vec::new();
+    };
+    lines
+        .filter(|(_, text)| text.len() != first.len())
+        .map(|(line, text)| {
+            let column = text.len().min(first.len()) + 1;
+            Finding::malformed(line, column, "expected row as wide as the first")
+        })
+        .collect()
+}
+
+pub fn solve_part1(input: &Input) -> usize {
+    let &Input {
+        ref grid,
+        width,
+        height,
+    } = input;
+    let input = grid;
     let index = |x: usize, y: usize| y * width + x;
 
     let mut count = 0;
@@ -13,12 +118,12 @@ pub fn part1(input: &str) -> usize {
     };
 
     // horizontal
-    for i in 0..(input.len() - 4) {
+    for i in 0..input.len().saturating_sub(4) {
         handle_value(&input[i..(i + 4)]);
     }
 
     // vertical
-    for y in 0..(height - 3) {
+    for y in 0..height.saturating_sub(3) {
         for x in 0..width {
             handle_value(&[
                 input[index(x, y)],
@@ -30,8 +135,8 @@ pub fn part1(input: &str) -> usize {
     }
 
     // diagonal 1
-    for y in 0..(height - 3) {
-        for x in 0..(width - 3) {
+    for y in 0..height.saturating_sub(3) {
+        for x in 0..width.saturating_sub(3) {
             handle_value(&[
                 input[index(x, y)],
                 input[index(x + 1, y + 1)],
@@ -41,7 +146,7 @@ pub fn part1(input: &str) -> usize {
         }
     }
 
-    for y in 0..(height - 3) {
+    for y in 0..height.saturating_sub(3) {
         for x in 3..width {
             handle_value(&[
                 input[index(x, y)],
@@ -55,15 +160,19 @@ pub fn part1(input: &str) -> usize {
     count
 }
 
-#[aoc(day4, part2)]
-pub fn part2(input: &str) -> u64 {
-    let (input, width, height) = parse_input(input);
+pub fn solve_part2(input: &Input) -> u64 {
+    let &Input {
+        ref grid,
+        width,
+        height,
+    } = input;
+    let input = grid;
     let index = |x: usize, y: usize| y * width + x;
 
     let mut count = 0;
 
-    for y in 0..(height - 2) {
-        for x in 0..(width - 2) {
+    for y in 0..height.saturating_sub(2) {
+        for x in 0..width.saturating_sub(2) {
             if input[index(x + 1, y + 1)] != b'A' {
                 continue;
             }
@@ -82,9 +191,11 @@ pub fn part2(input: &str) -> u64 {
     count
 }
 
-fn parse_input(input: &str) -> (Vec<u8>, usize, usize) {
-    let mut input = input.as_bytes().to_vec();
-    input.push(b'\n');
+fn parse_input(input: &str, grid: &mut Vec<u8>) -> Result<(usize, usize), Error> {
+    grid.clear();
+    grid.extend_from_slice(input.as_bytes());
+    grid.push(b'\n');
+    let input = grid;
     let width = input
         .iter()
         .copied()
@@ -93,6 +204,15 @@ fn parse_input(input: &str) -> (Vec<u8>, usize, usize) {
         .map(|(i, _)| i)
         .unwrap()
         + 1;
+
+    for (y, row) in input.chunks(width).enumerate() {
+        let end = row.iter().position(|&c| c == b'\n');
+        if end != Some(width - 1) && row != b"\n" {
+            let column = end.map_or(width, |x| x + 1);
+            return Err(Error::parse(y + 1, column, "row as wide as the first"));
+        }
+    }
+
     let height = (input.len() + 1) / width;
-    (input, width, height)
+    Ok((width, height))
 }
@@ -1,81 +1,751 @@
 use seq_macro::seq;
 use std::{
-    cmp::Ordering,
-    ops::{Index, IndexMut},
-    simd::{cmp::SimdPartialEq, simd_swizzle, u8x32, u8x64},
+    fmt,
+    ops::{Index, RangeInclusive},
+    simd::{
+        cmp::{SimdPartialEq, SimdPartialOrd},
+        simd_swizzle, u8x32, u8x64,
+    },
     str::FromStr,
 };
 
 use aoc_runner_derive::aoc;
 
+use crate::{
+    validate::{self, Finding},
+    Error, Solution,
+};
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    const NAME: &'static str = "Print Queue";
+
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type Scratch = Input;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse(input)
+    }
+
+    fn validate(input: &str) -> Vec<Finding> {
+        validate(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize, Error> {
+        Ok(solve_part1(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<usize, Error> {
+        solve_part2(input)
+    }
+
+    fn part1_with(input: &str, scratch: &mut Input) -> Result<usize, Error> {
+        parse_into(input, scratch)?;
+        Ok(solve_part1(scratch))
+    }
+
+    fn part2_with(input: &str, scratch: &mut Input) -> Result<usize, Error> {
+        parse_into(input, scratch)?;
+        solve_part2(scratch)
+    }
+
+    fn solve_with(input: &str, scratch: &mut Input) -> Result<(usize, usize), Error> {
+        parse_into(input, scratch)?;
+        Ok((solve_part1(scratch), solve_part2(scratch)?))
+    }
+}
+
 #[aoc(day5, part1)]
 pub fn part1(input: &str) -> usize {
-    let input = Input::from_str(input).unwrap();
-
-    input
-        .updates
-        .iter()
-        .filter(|(update, len)| is_update_valid(&update[0..(*len as usize)], &input.orderings))
-        .map(|(update, len)| update[(*len / 2) as usize] as usize)
-        .sum()
+    try_part1(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
 #[aoc(day5, part2)]
 pub fn part2(input: &str) -> usize {
-    let input = Input::from_str(input).unwrap();
+    try_part2(input).unwrap_or_else(|e| panic!("{e}"))
+}
 
+pub fn try_part1(input: &str) -> Result<usize, Error> {
+    Ok(solve_part1(&parse(input)?))
+}
+
+pub fn try_part2(input: &str) -> Result<usize, Error> {
+    solve_part2(&parse(input)?)
+}
+
+pub fn parse(input: &str) -> Result<Input, Error> {
+    Ok(Input::from_str(input)?)
+}
+
+/// Like [`parse`], but reuses the buffers in `parsed`.
+pub fn parse_into(input: &str, parsed: &mut Input) -> Result<(), Error> {
+    if parse_simd(input.as_bytes(), parsed).is_none() {
+        parse_scalar_into(input.as_bytes(), parsed)?;
+    }
+    Ok(())
+}
+
+/// Reports every malformed rule and update, pages outside [`PAGES`], updates
+/// longer than [`MAX_UPDATE_LEN`], updates whose rules form a cycle, and
+/// invalid updates without a unique correct order.
+pub fn validate(input: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut lines =
+        validate::lines(input).map(|(line, text)| (line, text.strip_suffix(b"\r").unwrap_or(text)));
+    let mut last_line = 0;
+    loop {
+        let Some((line, text)) = lines.next() else {
+            findings.push(Finding::malformed(
+                last_line + 1,
+                1,
+                "expected blank line between rules and updates",
+            ));
+            return findings;
+        };
+        last_line = line;
+        if text.is_empty() {
+            break;
+        }
+        let pages: Vec<_> = text.split(|&c| c == b'|').collect();
+        let mut column = 1;
+        for (i, page) in pages.iter().enumerate() {
+            if let Err(message) = validate_page(page) {
+                findings.push(Finding::malformed(line, column, message));
+            }
+            if i == 2 {
+                findings.push(Finding::malformed(
+                    line,
+                    column - 1,
+                    "expected rule `NN|NN`",
+                ));
+            }
+            column += page.len() + 1;
+        }
+        if pages.len() == 1 {
+            findings.push(Finding::malformed(
+                line,
+                text.len() + 1,
+                "expected rule `NN|NN`",
+            ));
+        }
+    }
+
+    for (line, text) in lines {
+        let mut column = 1;
+        for (i, page) in text.split(|&c| c == b',').enumerate() {
+            if i == MAX_UPDATE_LEN {
+                findings.push(Finding::malformed(
+                    line,
+                    column,
+                    format!("update has more than {MAX_UPDATE_LEN} pages"),
+                ));
+            }
+            if let Err(message) = validate_page(page) {
+                findings.push(Finding::malformed(line, column, message));
+            }
+            column += page.len() + 1;
+        }
+    }
+
+    if !findings.is_empty() {
+        return findings;
+    }
+    if let Ok(input) = parse(input) {
+        for (i, update) in input.updates().enumerate() {
+            let Err(conflict) = middle_page(update, &input.orderings) else {
+                continue;
+            };
+            // Neighbouring pages in rule order can still hide a cycle, but
+            // otherwise an update already in order needs no other rules.
+            if in_order(update, &input.orderings) && !matches!(conflict, OrderingConflict::Cycle(_))
+            {
+                continue;
+            }
+            findings.push(Finding::unsolvable(
+                input.update_line(i),
+                1,
+                conflict.to_string(),
+            ));
+        }
+    }
+    findings
+}
+
+fn validate_page(page: &[u8]) -> Result<(), String> {
+    let digits = page.iter().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        return Err("expected page number".to_string());
+    }
+    if digits != page.len() {
+        return Err(format!(
+            "unexpected `{}` after page number",
+            page[digits].escape_ascii()
+        ));
+    }
+    let number = page.iter().fold(0u64, |n, c| {
+        n.saturating_mul(10).saturating_add((c - b'0') as u64)
+    });
+    if digits != 2 || !PAGES.contains(&(number as u8)) {
+        return Err(format!(
+            "page {number} is outside {}..={}",
+            PAGES.start(),
+            PAGES.end()
+        ));
+    }
+    Ok(())
+}
+
+pub fn solve_part1(input: &Input) -> usize {
     input
-        .updates
-        .into_iter()
-        .filter(|(update, len)| !is_update_valid(&update[0..(*len as usize)], &input.orderings))
-        .map(|(mut update, len)| {
-            let update = &mut update[0..(len as usize)];
-            update.sort_unstable_by(|a, b| {
-                if input.orderings[(*a, *b)] {
-                    Ordering::Less
-                } else {
-                    Ordering::Greater
+        .updates()
+        .filter(|update| in_order(update, &input.orderings))
+        .map(|update| update[update.len() / 2] as usize)
+        .sum()
+}
+
+/// Fails if an invalid update has no unique correct order.
+pub fn solve_part2(input: &Input) -> Result<usize, Error> {
+    input
+        .updates()
+        .filter(|update| !in_order(update, &input.orderings))
+        .map(|update| Ok(middle_page(update, &input.orderings)? as usize))
+        .sum()
+}
+
+/// Whether the update already respects every rule. Fails on pages outside
+/// [`PAGES`], which no rule can mention.
+pub fn is_update_valid(update: &[u8], orderings: &Orderings) -> Result<bool, PageOutOfRange> {
+    check_pages(update)?;
+    Ok(in_order(update, orderings))
+}
+
+/// [`is_update_valid`] for updates known to hold only pages in [`PAGES`].
+fn in_order(update: &[u8], orderings: &Orderings) -> bool {
+    update.is_sorted_by(|a, b| orderings[(*a, *b)])
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderingConflict {
+    /// The rules between the pages of the update form a cycle. Each page must
+    /// precede the next, and the last page must precede the first.
+    Cycle(Vec<u8>),
+    /// No rule orders these pairs of pages relative to one another.
+    Ambiguous(Vec<(u8, u8)>),
+    /// A page outside [`PAGES`], which no rule can mention.
+    OutOfRange(PageOutOfRange),
+}
+
+impl fmt::Display for OrderingConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderingConflict::Cycle(cycle) => {
+                write!(f, "rules form a cycle: ")?;
+                for page in cycle {
+                    write!(f, "{page} -> ")?;
                 }
+                write!(f, "{}", cycle[0])
+            }
+            OrderingConflict::Ambiguous(pairs) => {
+                write!(f, "no rule orders pages ")?;
+                for (i, (a, b)) in pairs.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{a} and {b}")?;
+                }
+                Ok(())
+            }
+            OrderingConflict::OutOfRange(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for OrderingConflict {}
+
+impl From<PageOutOfRange> for OrderingConflict {
+    fn from(error: PageOutOfRange) -> Self {
+        OrderingConflict::OutOfRange(error)
+    }
+}
+
+/// Orders the pages of an update so that every rule between them is respected.
+///
+/// Succeeds only when the rules restricted to the update are acyclic and order
+/// every pair of pages, in which case the order is unique.
+pub fn topological_order(
+    update: &[u8],
+    orderings: &Orderings,
+) -> Result<Vec<u8>, OrderingConflict> {
+    check_pages(update)?;
+    let members = update.iter().fold(0, |mask, &page| mask | bit(page));
+
+    if let Some(order) = total_order(update, members, orderings) {
+        return Ok(order);
+    }
+
+    let mut order = Vec::with_capacity(update.len());
+    let mut remaining = members;
+    while remaining != 0 {
+        let Some(page) =
+            pages(remaining).find(|&page| orderings.predecessors[page as usize] & remaining == 0)
+        else {
+            return Err(OrderingConflict::Cycle(find_cycle(remaining, orderings)));
+        };
+        order.push(page);
+        remaining &= !bit(page);
+    }
+
+    let mut ambiguous = Vec::new();
+    for (i, &a) in update.iter().enumerate() {
+        for &b in &update[(i + 1)..] {
+            if !orderings[(a, b)] && !orderings[(b, a)] {
+                ambiguous.push((a, b));
+            }
+        }
+    }
+    if !ambiguous.is_empty() {
+        return Err(OrderingConflict::Ambiguous(ambiguous));
+    }
+
+    Ok(order)
+}
+
+/// Sorts the update in place according to [`topological_order`].
+pub fn fix_update(update: &mut [u8], orderings: &Orderings) -> Result<(), OrderingConflict> {
+    let order = topological_order(update, orderings)?;
+    update.copy_from_slice(&order);
+    Ok(())
+}
+
+/// Fast path for [`topological_order`]: when exactly one rule orders every
+/// pair of distinct pages, the rules are acyclic iff each page is preceded by
+/// a different number of the others, and that number is its position.
+fn total_order(update: &[u8], members: u128, orderings: &Orderings) -> Option<Vec<u8>> {
+    let mut order = vec![0; update.len()];
+    ranks(update, members, orderings, |rank, page| order[rank] = page)?;
+    Some(order)
+}
+
+/// Calls `place` with the position of every page in the update if
+/// [`total_order`] applies.
+fn ranks(
+    update: &[u8],
+    members: u128,
+    orderings: &Orderings,
+    mut place: impl FnMut(usize, u8),
+) -> Option<()> {
+    if members.count_ones() as usize != update.len() {
+        return None;
+    }
+
+    let mut seen = 0u128;
+    for &page in update {
+        let before = orderings.predecessors[page as usize] & members;
+        let after = orderings.successors[page as usize] & members;
+        if before & after != 0 || before | after != members & !bit(page) {
+            return None;
+        }
+
+        let rank = before.count_ones();
+        if seen & (1 << rank) != 0 {
+            return None;
+        }
+        seen |= 1 << rank;
+        place(rank as usize, page);
+    }
+
+    Some(())
+}
+
+/// The middle page of [`topological_order`]. Only builds the order, and the
+/// conflict if there is one, when the rules do not fully order the update.
+fn middle_page(update: &[u8], orderings: &Orderings) -> Result<u8, OrderingConflict> {
+    let members = update.iter().fold(0, |mask, &page| mask | bit(page));
+    let mut seen = 0u32;
+    let mut middle = 0;
+    for &page in update {
+        let before = orderings.predecessors[page as usize] & members;
+        let after = orderings.successors[page as usize] & members;
+        if before & after != 0 || before | after != members & !bit(page) {
+            return Ok(topological_order(update, orderings)?[update.len() / 2]);
+        }
+        let rank = before.count_ones();
+        seen |= 1 << rank;
+        if rank as usize == update.len() / 2 {
+            middle = page;
+        }
+    }
+    if seen.count_ones() as usize != update.len() {
+        return Ok(topological_order(update, orderings)?[update.len() / 2]);
+    }
+    Ok(middle)
+}
+
+fn find_cycle(remaining: u128, orderings: &Orderings) -> Vec<u8> {
+    let mut walk = Vec::new();
+    let mut page = remaining.trailing_zeros() as u8;
+    loop {
+        if let Some(start) = walk.iter().position(|&p| p == page) {
+            let mut cycle = walk.split_off(start);
+            cycle.reverse();
+            return cycle;
+        }
+        walk.push(page);
+        page = (orderings.predecessors[page as usize] & remaining).trailing_zeros() as u8;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// `before` must come first according to the rule on `rule_line`, but
+    /// appears after `after`. Indices are positions within the update.
+    BrokenRule {
+        before: u8,
+        before_index: usize,
+        after: u8,
+        after_index: usize,
+        rule_line: usize,
+    },
+    /// Neighbouring pages that no rule orders relative to one another.
+    Unordered {
+        first: u8,
+        first_index: usize,
+        second: u8,
+        second_index: usize,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::BrokenRule {
+                before,
+                before_index,
+                after,
+                after_index,
+                rule_line,
+            } => write!(
+                f,
+                "page {before} (index {before_index}) must come before page {after} \
+                 (index {after_index}) per rule `{before}|{after}` on line {rule_line}"
+            ),
+            Violation::Unordered {
+                first,
+                first_index,
+                second,
+                second_index,
+            } => write!(
+                f,
+                "no rule orders page {first} (index {first_index}) \
+                 and page {second} (index {second_index})"
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidUpdate {
+    pub line: usize,
+    pub pages: Vec<u8>,
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for InvalidUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "update on line {} is invalid:", self.line)?;
+        for violation in &self.violations {
+            write!(f, "\n  {violation}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists every rule the update breaks, and any neighbouring pages that no rule
+/// orders, which together are why [`is_update_valid`] would reject it.
+///
+/// Broken rules are found in a single pass over the rules, so each one carries
+/// the line of its first occurrence without searching for it.
+pub fn explain_update(update: &[u8], input: &Input) -> Vec<Violation> {
+    let members = update
+        .iter()
+        .filter(|page| PAGES.contains(page))
+        .fold(0, |mask, &page| mask | bit(page));
+    let mut seen = Orderings::new();
+    let mut broken = Vec::new();
+    for (i, &(before, after)) in input.rules.iter().enumerate() {
+        if members & bit(before) == 0 || members & bit(after) == 0 || seen[(before, after)] {
+            continue;
+        }
+        seen.insert(before, after);
+        for (after_index, &page) in update.iter().enumerate() {
+            if page != after {
+                continue;
+            }
+            for (before_index, &page) in update.iter().enumerate().skip(after_index + 1) {
+                if page == before {
+                    broken.push((after_index, before_index, i + 1));
+                }
+            }
+        }
+    }
+    broken.sort_unstable();
+
+    let mut violations: Vec<_> = broken
+        .into_iter()
+        .map(
+            |(after_index, before_index, rule_line)| Violation::BrokenRule {
+                before: update[before_index],
+                before_index,
+                after: update[after_index],
+                after_index,
+                rule_line,
+            },
+        )
+        .collect();
+
+    for (i, pair) in update.windows(2).enumerate() {
+        let (a, b) = (pair[0], pair[1]);
+        if !input.orderings.must_precede(a, b) && !input.orderings.must_precede(b, a) {
+            violations.push(Violation::Unordered {
+                first: a,
+                first_index: i,
+                second: b,
+                second_index: i + 1,
             });
-            update[(len / 2) as usize] as usize
+        }
+    }
+
+    violations
+}
+
+pub fn explain_invalid_updates(input: &Input) -> Vec<InvalidUpdate> {
+    input
+        .updates()
+        .enumerate()
+        .filter(|(_, update)| !in_order(update, &input.orderings))
+        .map(|(i, update)| InvalidUpdate {
+            line: input.update_line(i),
+            pages: update.to_vec(),
+            violations: explain_update(update, input),
         })
-        .sum()
+        .collect()
 }
 
-fn is_update_valid(update: &[u8], orderings: &Orderings) -> bool {
-    update.is_sorted_by(|a, b| orderings[(*a, *b)])
+pub const PAGES: RangeInclusive<u8> = 10..=99;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageOutOfRange(pub u8);
+
+impl fmt::Display for PageOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "page {} is not between {} and {}",
+            self.0,
+            PAGES.start(),
+            PAGES.end()
+        )
+    }
 }
 
-struct Orderings([bool; Self::SIZE]);
+impl std::error::Error for PageOutOfRange {}
+
+/// Rule set, stored as one bitset of pages per page in each direction.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Orderings {
+    successors: [u128; 128],
+    predecessors: [u128; 128],
+}
 
 impl Orderings {
-    const SIZE: usize = 0b1100011_1100011;
+    pub fn new() -> Self {
+        Self {
+            successors: [0; 128],
+            predecessors: [0; 128],
+        }
+    }
+
+    /// Adds the rule `before|after`.
+    pub fn add_rule(&mut self, before: u8, after: u8) -> Result<(), PageOutOfRange> {
+        check_pages(&[before, after])?;
+        self.insert(before, after);
+        Ok(())
+    }
+
+    fn insert(&mut self, before: u8, after: u8) {
+        self.successors[before as usize] |= bit(after);
+        self.predecessors[after as usize] |= bit(before);
+    }
 
-    fn offset(&self, a: u8, b: u8) -> usize {
-        ((a as usize) << 7) | (b as usize)
+    /// Whether a rule says that `a` must come before `b`, which no rule does
+    /// for pages outside [`PAGES`].
+    pub fn must_precede(&self, a: u8, b: u8) -> bool {
+        check_pages(&[a, b]).is_ok() && self.successors[a as usize] & bit(b) != 0
+    }
+
+    /// Every rule as a `(before, after)` pair, sorted.
+    pub fn rules(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        (0..128u8).flat_map(move |before| {
+            pages(self.successors[before as usize]).map(move |after| (before, after))
+        })
+    }
+
+    pub fn closure(&self) -> Closure<'_> {
+        Closure::new(self)
+    }
+}
+
+/// Transitive closure of an [`Orderings`]: `a` precedes `b` if a chain of
+/// rules leads from `a` to `b`.
+#[derive(Clone)]
+pub struct Closure<'a> {
+    orderings: &'a Orderings,
+    successors: [u128; 128],
+    predecessors: [u128; 128],
+}
+
+impl<'a> Closure<'a> {
+    fn new(orderings: &'a Orderings) -> Self {
+        let mut successors = orderings.successors;
+        for via in 0..128 {
+            let through = successors[via];
+            for reach in successors.iter_mut() {
+                if *reach & bit(via as u8) != 0 {
+                    *reach |= through;
+                }
+            }
+        }
+
+        let mut predecessors = [0; 128];
+        for (before, &after) in successors.iter().enumerate() {
+            for after in pages(after) {
+                predecessors[after as usize] |= bit(before as u8);
+            }
+        }
+
+        Self {
+            orderings,
+            successors,
+            predecessors,
+        }
+    }
+
+    /// Whether a chain of rules leads from `a` to `b`, which none does for
+    /// pages outside [`PAGES`].
+    pub fn precedes(&self, a: u8, b: u8) -> bool {
+        check_pages(&[a, b]).is_ok() && self.successors[a as usize] & bit(b) != 0
+    }
+
+    /// Whether the rules imply any ordering between `a` and `b`, in either
+    /// direction.
+    pub fn is_ordered(&self, a: u8, b: u8) -> bool {
+        self.precedes(a, b) || self.precedes(b, a)
+    }
+
+    /// All pages that must come before `page`.
+    pub fn predecessors(&self, page: u8) -> impl Iterator<Item = u8> {
+        pages(if PAGES.contains(&page) {
+            self.predecessors[page as usize]
+        } else {
+            0
+        })
+    }
+
+    /// All pages that must come after `page`.
+    pub fn successors(&self, page: u8) -> impl Iterator<Item = u8> {
+        pages(if PAGES.contains(&page) {
+            self.successors[page as usize]
+        } else {
+            0
+        })
+    }
+
+    pub fn is_acyclic(&self) -> bool {
+        self.cyclic_pages().next().is_none()
+    }
+
+    /// Pages that must come before themselves.
+    pub fn cyclic_pages(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..128u8).filter(|&page| self.precedes(page, page))
+    }
+
+    /// A cycle of rules, each page preceding the next and the last preceding
+    /// the first, if the rule set has any.
+    pub fn find_cycle(&self) -> Option<Vec<u8>> {
+        let page = self.cyclic_pages().next()?;
+        let component = self.successors[page as usize] & self.predecessors[page as usize];
+        Some(find_cycle(component, self.orderings))
     }
 }
 
 impl Default for Orderings {
     fn default() -> Self {
-        Self([false; Self::SIZE])
+        Self::new()
     }
 }
 
+/// Writes the rules in the puzzle's `NN|NN` format, one per line.
+impl fmt::Display for Orderings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (before, after) in self.rules() {
+            writeln!(f, "{before}|{after}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Orderings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(
+                self.rules()
+                    .map(|(before, after)| format!("{before}|{after}")),
+            )
+            .finish()
+    }
+}
+
+/// Unlike [`Orderings::must_precede`], only for pages in [`PAGES`], which the
+/// parser and [`Orderings::add_rule`] have already checked.
 impl Index<(u8, u8)> for Orderings {
     type Output = bool;
 
     fn index(&self, (a, b): (u8, u8)) -> &Self::Output {
-        &self.0[self.offset(a, b)]
+        if self.successors[a as usize] & bit(b) != 0 {
+            &true
+        } else {
+            &false
+        }
     }
 }
 
-impl IndexMut<(u8, u8)> for Orderings {
-    fn index_mut(&mut self, (a, b): (u8, u8)) -> &mut Self::Output {
-        &mut self.0[self.offset(a, b)]
+/// Only for pages in [`PAGES`], which the public API checks for.
+fn bit(page: u8) -> u128 {
+    1 << page
+}
+
+fn check_pages(pages: &[u8]) -> Result<(), PageOutOfRange> {
+    match pages.iter().find(|page| !PAGES.contains(page)) {
+        Some(&page) => Err(PageOutOfRange(page)),
+        None => Ok(()),
     }
 }
 
+fn pages(mut mask: u128) -> impl Iterator<Item = u8> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            return None;
+        }
+        let page = mask.trailing_zeros() as u8;
+        mask &= mask - 1;
+        Some(page)
+    })
+}
+
 macro_rules! swizzle_x64_radix_3_with_offset {
     ( $data:expr, $offset:expr ) => {
         simd_swizzle!($data, seq!(N in 0..32 {
@@ -88,115 +758,395 @@ macro_rules! swizzle_x64_radix_3_with_offset {
     };
 }
 
-struct Input {
+pub const MAX_UPDATE_LEN: usize = 23;
+
+#[derive(Debug)]
+pub struct Input {
     orderings: Orderings,
-    updates: Vec<([u8; 23], u8)>,
+    rules: Vec<(u8, u8)>,
+    updates: Vec<([u8; MAX_UPDATE_LEN], u8)>,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Input {
+            orderings: Orderings::default(),
+            rules: Vec::with_capacity(2048),
+            updates: Vec::with_capacity(256),
+        }
+    }
+}
+
+impl Input {
+    fn clear(&mut self) {
+        self.orderings = Orderings::default();
+        self.rules.clear();
+        self.updates.clear();
+    }
+
+    pub fn orderings(&self) -> &Orderings {
+        &self.orderings
+    }
+
+    pub fn updates(&self) -> impl Iterator<Item = &[u8]> {
+        self.updates
+            .iter()
+            .map(|(update, len)| &update[0..(*len as usize)])
+    }
+
+    /// Line of the first `before|after` rule in the input.
+    pub fn rule_line(&self, before: u8, after: u8) -> Option<usize> {
+        self.rules
+            .iter()
+            .position(|&rule| rule == (before, after))
+            .map(|i| i + 1)
+    }
+
+    /// Line of the update at `index` in the input.
+    pub fn update_line(&self, index: usize) -> usize {
+        self.rules.len() + 2 + index
+    }
+}
+
+/// Pages past the end of an update are not compared.
+impl PartialEq for Input {
+    fn eq(&self, other: &Self) -> bool {
+        self.rules == other.rules && self.updates().eq(other.updates())
+    }
 }
 
+impl Eq for Input {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The blank line between the rules and the updates is missing, and would
+    /// be expected on `line`.
+    MissingSeparator {
+        line: usize,
+    },
+    MalformedRule {
+        line: usize,
+        column: usize,
+    },
+    MalformedUpdate {
+        line: usize,
+        column: usize,
+    },
+    /// Pages are two-digit numbers in [`PAGES`].
+    PageOutOfRange {
+        line: usize,
+        column: usize,
+        page: u64,
+    },
+    UpdateTooLong {
+        line: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingSeparator { line } => {
+                write!(
+                    f,
+                    "line {line}: missing blank line between rules and updates"
+                )
+            }
+            ParseError::MalformedRule { line, column } => {
+                write!(
+                    f,
+                    "line {line}, column {column}: malformed rule, expected `NN|NN`"
+                )
+            }
+            ParseError::MalformedUpdate { line, column } => {
+                write!(
+                    f,
+                    "line {line}, column {column}: malformed update, expected `NN,NN,...`"
+                )
+            }
+            ParseError::PageOutOfRange { line, column, page } => {
+                write!(
+                    f,
+                    "line {line}, column {column}: page {page} is not between {} and {}",
+                    PAGES.start(),
+                    PAGES.end()
+                )
+            }
+            ParseError::UpdateTooLong { line } => {
+                write!(
+                    f,
+                    "line {line}: update has more than {MAX_UPDATE_LEN} pages"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 impl FromStr for Input {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let input = input.as_bytes();
-        let mut orderings = Orderings::default();
-        let mut updates = Vec::with_capacity(256);
+        let mut parsed = Input::default();
+        if parse_simd(input.as_bytes(), &mut parsed).is_none() {
+            parse_scalar_into(input.as_bytes(), &mut parsed)?;
+        }
+        Ok(parsed)
+    }
+}
 
-        let newline = u8x64::splat(b'\n');
+/// Fast path for well-formed input. Gives up on anything unexpected, leaving
+/// [`parse_scalar`] to either accept the input or locate the error.
+fn parse_simd(input: &[u8], parsed: &mut Input) -> Option<()> {
+    // Scalar on wasm32, see `crate::wasm`.
+    if cfg!(target_arch = "wasm32") {
+        return None;
+    }
+    parsed.clear();
+    let Input {
+        orderings,
+        rules,
+        updates,
+    } = parsed;
 
-        let mut curr = input.len();
-        loop {
-            curr -= size_of::<u8x64>();
-            let d = u8x64::from_slice(&input[curr..]);
-            let newlines = d.simd_eq(newline).to_bitmask();
-            if (newlines & (newlines >> 1)) != 0 {
-                break;
-            }
-            curr += 1;
+    let newline = u8x64::splat(b'\n');
+
+    let mut curr = input.len();
+    loop {
+        curr = curr.checked_sub(size_of::<u8x64>())?;
+        let d = u8x64::from_slice(&input[curr..]);
+        let newlines = d.simd_eq(newline).to_bitmask();
+        if (newlines & (newlines >> 1)) != 0 {
+            break;
         }
+        curr += 1;
+    }
 
-        let mut iter = curr..;
-        let ordering_end = loop {
-            let i = iter.next().unwrap();
-            if input[i] == b'\n' && input[i + 1] == b'\n' {
-                break i;
-            }
-        };
+    let ordering_end = curr + input[curr..].windows(2).position(|w| w == b"\n\n")?;
 
-        let mut ordering_input = &input[0..(ordering_end + 1)];
-        let mut updates_input = &input[(ordering_end + 2)..];
+    let mut ordering_input = &input[0..(ordering_end + 1)];
+    let mut updates_input = &input[(ordering_end + 2)..];
 
-        ordering_input = simd_parse_21_two_digit_numbers_with_trailers(
-            ordering_input,
-            |_| (),
-            |nums, ()| {
-                for [before, after] in nums.as_array().array_chunks::<2>().take(10) {
-                    orderings[(*before, *after)] = true;
-                }
-                4
-            },
-        );
+    let rule_trailers = u8x32::from_array(array_init::array_init(|i| {
+        if i % 2 == 0 {
+            b'|'
+        } else {
+            b'\n'
+        }
+    }));
+    ordering_input = simd_parse_21_two_digit_numbers_with_trailers(
+        ordering_input,
+        |orig| swizzle_x64_radix_3_with_offset!(orig, 2),
+        |nums, valid, trailers| {
+            const RULE_NUMS: u64 = (1 << 20) - 1;
+            let trailers_valid = trailers.simd_eq(rule_trailers).to_bitmask();
+            if (valid & trailers_valid & RULE_NUMS) != RULE_NUMS {
+                return None;
+            }
+            for [before, after] in nums.as_array().array_chunks::<2>().take(10) {
+                orderings.insert(*before, *after);
+                rules.push((*before, *after));
+            }
+            Some(4)
+        },
+    )?;
 
-        for ordering in ordering_input.array_chunks::<6>() {
-            let before = parse_10_to_99(ordering[0], ordering[1]);
-            let after = parse_10_to_99(ordering[3], ordering[4]);
-            orderings[(before, after)] = true;
+    let mut ordering_iter = ordering_input.array_chunks::<6>();
+    for ordering in &mut ordering_iter {
+        if ordering[2] != b'|' || ordering[5] != b'\n' {
+            return None;
         }
+        let before = parse_10_to_99(ordering[0], ordering[1])?;
+        let after = parse_10_to_99(ordering[3], ordering[4])?;
+        orderings.insert(before, after);
+        rules.push((before, after));
+    }
+    if !ordering_iter.remainder().is_empty() {
+        return None;
+    }
 
-        let newline = u8x32::splat(b'\n');
-        let mut pages = [0; 23];
-        let mut len = 0;
-        updates_input = simd_parse_21_two_digit_numbers_with_trailers(
-            updates_input,
-            |orig| swizzle_x64_radix_3_with_offset!(orig, 2),
-            |nums, separators| {
-                for (n, is_newline) in nums
-                    .as_array()
-                    .into_iter()
-                    .copied()
-                    .zip(separators.simd_eq(newline).to_array().into_iter())
-                    .take(21)
-                {
-                    pages[len] = n;
-                    len += 1;
-                    if is_newline {
-                        updates.push((pages, len as u8));
-                        pages = [0; 23];
-                        len = 0;
-                    }
+    let newline = u8x32::splat(b'\n');
+    let comma = u8x32::splat(b',');
+    let mut pages = [0; MAX_UPDATE_LEN];
+    let mut len = 0;
+    updates_input = simd_parse_21_two_digit_numbers_with_trailers(
+        updates_input,
+        |orig| swizzle_x64_radix_3_with_offset!(orig, 2),
+        |nums, valid, separators| {
+            const UPDATE_NUMS: u64 = (1 << 21) - 1;
+            let is_newline = separators.simd_eq(newline);
+            let is_comma = separators.simd_eq(comma);
+            if (valid & (is_newline | is_comma).to_bitmask() & UPDATE_NUMS) != UPDATE_NUMS {
+                return None;
+            }
+
+            for (n, is_newline) in nums
+                .as_array()
+                .iter()
+                .copied()
+                .zip(is_newline.to_array().into_iter())
+                .take(21)
+            {
+                if len == MAX_UPDATE_LEN {
+                    return None;
                 }
+                pages[len] = n;
+                len += 1;
+                if is_newline {
+                    updates.push((pages, len as u8));
+                    pages = [0; MAX_UPDATE_LEN];
+                    len = 0;
+                }
+            }
 
-                1
-            },
-        );
+            Some(1)
+        },
+    )?;
 
-        let mut update_iter = updates_input.array_chunks::<3>();
-        for update in &mut update_iter {
-            pages[len] = parse_10_to_99(update[0], update[1]);
-            len += 1;
-            if update[2] == b'\n' {
-                updates.push((pages, len as u8));
-                pages = [0; 23];
-                len = 0;
-            }
+    let mut update_iter = updates_input.array_chunks::<3>();
+    for update in &mut update_iter {
+        if len == MAX_UPDATE_LEN || (update[2] != b',' && update[2] != b'\n') {
+            return None;
+        }
+        pages[len] = parse_10_to_99(update[0], update[1])?;
+        len += 1;
+        if update[2] == b'\n' {
+            updates.push((pages, len as u8));
+            pages = [0; MAX_UPDATE_LEN];
+            len = 0;
         }
+    }
 
-        let rem = update_iter.remainder();
-        if !rem.is_empty() {
-            pages[len] = parse_10_to_99(rem[0], rem[1]);
+    match update_iter.remainder() {
+        [] => {}
+        [a, b] if len < MAX_UPDATE_LEN => {
+            pages[len] = parse_10_to_99(*a, *b)?;
             len += 1;
             updates.push((pages, len as u8));
+            len = 0;
+        }
+        _ => return None,
+    }
+    if len != 0 {
+        return None;
+    }
+
+    Some(())
+}
+
+/// Reference parser that [`Input::from_str`] falls back on. It accepts the same
+/// inputs as the SIMD fast path, only slower, and also `\r\n` line endings.
+pub fn parse_scalar(input: &str) -> Result<Input, ParseError> {
+    let mut parsed = Input::default();
+    parse_scalar_into(input.as_bytes(), &mut parsed)?;
+    Ok(parsed)
+}
+
+fn parse_scalar_into(input: &[u8], parsed: &mut Input) -> Result<(), ParseError> {
+    parsed.clear();
+    let Input {
+        orderings,
+        rules,
+        updates,
+    } = parsed;
+
+    if input.is_empty() {
+        return Err(ParseError::MissingSeparator { line: 1 });
+    }
+
+    let input = input.strip_suffix(b"\n").unwrap_or(input);
+    let mut lines = input
+        .split(|&c| c == b'\n')
+        .enumerate()
+        .map(|(i, text)| (i + 1, text.strip_suffix(b"\r").unwrap_or(text)));
+
+    let mut last_line = 0;
+    loop {
+        let Some((line, text)) = lines.next() else {
+            return Err(ParseError::MissingSeparator {
+                line: last_line + 1,
+            });
+        };
+        last_line = line;
+        if text.is_empty() {
+            break;
+        }
+
+        let malformed = |column| ParseError::MalformedRule { line, column };
+        let (before, i) = parse_page(text, 0, line, malformed)?;
+        if text.get(i) != Some(&b'|') {
+            return Err(malformed(i + 1));
+        }
+        let (after, i) = parse_page(text, i + 1, line, malformed)?;
+        if i != text.len() {
+            return Err(malformed(i + 1));
         }
+        orderings.insert(before, after);
+        rules.push((before, after));
+    }
 
-        Ok(Input { orderings, updates })
+    for (line, text) in lines {
+        let malformed = |column| ParseError::MalformedUpdate { line, column };
+        let mut pages = [0; MAX_UPDATE_LEN];
+        let mut len = 0;
+        let mut i = 0;
+        loop {
+            if len == MAX_UPDATE_LEN {
+                return Err(ParseError::UpdateTooLong { line });
+            }
+            let (page, end) = parse_page(text, i, line, malformed)?;
+            pages[len] = page;
+            len += 1;
+            match text.get(end) {
+                None => break,
+                Some(b',') => i = end + 1,
+                Some(_) => return Err(malformed(end + 1)),
+            }
+        }
+        updates.push((pages, len as u8));
     }
+
+    Ok(())
+}
+
+fn parse_page(
+    text: &[u8],
+    start: usize,
+    line: usize,
+    malformed: impl Fn(usize) -> ParseError,
+) -> Result<(u8, usize), ParseError> {
+    let digits = text[start..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    if digits == 0 {
+        return Err(malformed(start + 1));
+    }
+
+    let end = start + digits;
+    let page = text[start..end].iter().fold(0u64, |n, c| {
+        n.saturating_mul(10).saturating_add((c - b'0') as u64)
+    });
+    if digits != 2 || !PAGES.contains(&(page as u8)) {
+        return Err(ParseError::PageOutOfRange {
+            line,
+            column: start + 1,
+            page,
+        });
+    }
+
+    Ok((page as u8, end))
 }
 
 fn simd_parse_21_two_digit_numbers_with_trailers<S>(
     mut input: &[u8],
     mut make_supplemental: impl FnMut(u8x64) -> S,
-    mut accept: impl FnMut(u8x32, S) -> usize,
-) -> &[u8] {
+    mut accept: impl FnMut(u8x32, u64, S) -> Option<usize>,
+) -> Option<&[u8]> {
     let zero_ascii = u8x64::splat(b'0');
+    let one = u8x32::splat(1);
+    let nine = u8x32::splat(9);
     let ten = u8x32::splat(10);
 
     while let Some(chunk) = input.array_chunks::<64>().next() {
@@ -204,15 +1154,19 @@ fn simd_parse_21_two_digit_numbers_with_trailers<S>(
         let d = orig - zero_ascii;
         let tens = swizzle_x64_radix_3_with_offset!(d, 0);
         let ones = swizzle_x64_radix_3_with_offset!(d, 1);
+        let valid = tens.simd_ge(one) & tens.simd_le(nine) & ones.simd_le(nine);
         let mut nums = tens * ten;
         nums += ones;
 
-        let unread = accept(nums, make_supplemental(orig));
+        let unread = accept(nums, valid.to_bitmask(), make_supplemental(orig))?;
         input = &input[(64 - unread)..];
     }
-    input
+    Some(input)
 }
 
-fn parse_10_to_99(a: u8, b: u8) -> u8 {
-    10 * (a - b'0') + (b - b'0')
+fn parse_10_to_99(a: u8, b: u8) -> Option<u8> {
+    match (a, b) {
+        (b'1'..=b'9', b'0'..=b'9') => Some(10 * (a - b'0') + (b - b'0')),
+        _ => None,
+    }
 }
@@ -1,17 +1,222 @@
-use aoc_runner_derive::aoc;
 use nalgebra::DMatrix;
 
+use aoc_runner_derive::aoc;
+
+use crate::{
+    validate::{self, Finding},
+    Error, Solution,
+};
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    const NAME: &'static str = "Guard Gallivant";
+
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+    type Scratch = Scratch;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse(input)
+    }
+
+    fn validate(input: &str) -> Vec<Finding> {
+        validate(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize, Error> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<usize, Error> {
+        solve_part2(input)
+    }
+
+    fn part1_with(input: &str, scratch: &mut Scratch) -> Result<usize, Error> {
+        parse_into(input, &mut scratch.input)?;
+        count_visited(&scratch.input, &mut scratch.visited)
+    }
+
+    fn part2_with(input: &str, scratch: &mut Scratch) -> Result<usize, Error> {
+        parse_into(input, &mut scratch.input)?;
+        let Scratch {
+            input,
+            visited,
+            visited_with_dir,
+        } = scratch;
+        count_loops(input, visited, visited_with_dir)
+    }
+
+    fn solve_with(input: &str, scratch: &mut Scratch) -> Result<(usize, usize), Error> {
+        parse_into(input, &mut scratch.input)?;
+        let Scratch {
+            input,
+            visited,
+            visited_with_dir,
+        } = scratch;
+        let part1 = count_visited(input, visited)?;
+        Ok((part1, count_loops(input, visited, visited_with_dir)?))
+    }
+}
+
 #[aoc(day6, part1)]
 pub fn part1(input: &str) -> usize {
-    let (grid, x, y, dir) = parse_input(input);
-    let visited = get_visited_cells(&grid, x, y, dir);
-    visited.iter().filter(|&&v| v).count()
+    try_part1(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
 #[aoc(day6, part2)]
 pub fn part2(input: &str) -> usize {
-    let (mut grid, x, y, dir) = parse_input(input);
-    let visited = get_visited_cells(&grid, x, y, dir);
+    try_part2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_part1(input: &str) -> Result<usize, Error> {
+    solve_part1(&parse(input)?)
+}
+
+pub fn try_part2(input: &str) -> Result<usize, Error> {
+    solve_part2(&parse(input)?)
+}
+
+/// The lab map and where the guard starts out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    grid: DMatrix<Cell>,
+    x: usize,
+    y: usize,
+    dir: Direction,
+}
+
+impl Default for Input {
+    fn default() -> Self {
+        Input {
+            grid: DMatrix::from_element(0, 0, Cell::Empty),
+            x: 0,
+            y: 0,
+            dir: Direction::Up,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Scratch {
+    input: Input,
+    visited: DMatrix<bool>,
+    visited_with_dir: DMatrix<[bool; 4]>,
+}
+
+impl Default for Scratch {
+    fn default() -> Self {
+        Scratch {
+            input: Input::default(),
+            visited: DMatrix::from_element(0, 0, false),
+            visited_with_dir: DMatrix::from_element(0, 0, [false; 4]),
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Input, Error> {
+    let mut parsed = Input::default();
+    parse_into(input, &mut parsed)?;
+    Ok(parsed)
+}
+
+/// Like [`parse`], but reuses the map in `parsed` if it is the same size.
+pub fn parse_into(input: &str, parsed: &mut Input) -> Result<(), Error> {
+    (parsed.x, parsed.y, parsed.dir) = parse_input(input, &mut parsed.grid)?;
+    Ok(())
+}
+
+/// Reports rows not as wide as the first, cells other than `.#^v<>`, any
+/// guard after the first, and a guard that walks in a loop forever.
+pub fn validate(input: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut lines = validate::lines(input).peekable();
+    let width = match lines.peek() {
+        Some((_, first)) if !first.is_empty() => first.len(),
+        _ => return vec![Finding::malformed(1, 1, "expected map")],
+    };
+    let mut guard = None;
+    for (line, text) in lines {
+        if text.len() != width {
+            let column = text.len().min(width) + 1;
+            findings.push(Finding::malformed(
+                line,
+                column,
+                "expected row as wide as the first",
+            ));
+        }
+        for (x, c) in text.iter().enumerate() {
+            match c {
+                b'.' | b'#' => {}
+                b'^' | b'v' | b'<' | b'>' if guard.is_none() => guard = Some((line, x + 1)),
+                b'^' | b'v' | b'<' | b'>' => {
+                    findings.push(Finding::malformed(line, x + 1, "second guard on the map"))
+                }
+                _ => findings.push(Finding::malformed(line, x + 1, "expected one of `.#^v<>`")),
+            }
+        }
+    }
+    let Some((line, column)) = guard else {
+        findings.push(Finding::malformed(1, 1, "no guard on the map"));
+        return findings;
+    };
+
+    if !findings.is_empty() {
+        return findings;
+    }
+    if let Ok(input) = parse(input) {
+        let mut visited_with_dir = DMatrix::from_element(0, 0, [false; 4]);
+        if !leaves(
+            &input.grid,
+            input.x,
+            input.y,
+            input.dir,
+            &mut visited_with_dir,
+        ) {
+            findings.push(Finding::unsolvable(
+                line,
+                column,
+                "the guard never leaves the map",
+            ));
+        }
+    }
+    findings
+}
+
+pub fn solve_part1(input: &Input) -> Result<usize, Error> {
+    count_visited(input, &mut DMatrix::from_element(0, 0, false))
+}
+
+pub fn solve_part2(input: &Input) -> Result<usize, Error> {
+    count_loops(
+        &mut input.clone(),
+        &mut DMatrix::from_element(0, 0, false),
+        &mut DMatrix::from_element(0, 0, [false; 4]),
+    )
+}
+
+fn count_visited(input: &Input, visited: &mut DMatrix<bool>) -> Result<usize, Error> {
+    let Input { grid, x, y, dir } = input;
+    get_visited_cells(grid, *x, *y, *dir, visited)?;
+    Ok(visited.iter().filter(|&&v| v).count())
+}
+
+/// Counts the obstructions that would trap the guard, trying each one out on
+/// `input`'s map and putting it back afterwards.
+fn count_loops(
+    input: &mut Input,
+    visited: &mut DMatrix<bool>,
+    visited_with_dir: &mut DMatrix<[bool; 4]>,
+) -> Result<usize, Error> {
+    let &mut Input {
+        ref mut grid,
+        x,
+        y,
+        dir,
+    } = input;
+    get_visited_cells(grid, x, y, dir, visited)?;
     let mut looping_blocks = 0;
     for block_y in 0..grid.nrows() {
         for block_x in 0..grid.ncols() {
@@ -19,82 +224,129 @@ pub fn part2(input: &str) -> usize {
                 continue;
             }
             grid[(block_y, block_x)] = Cell::Wall;
-            {
-                let mut visited_with_dir =
-                    DMatrix::from_fn(grid.nrows(), grid.ncols(), |_, _| [false; 4]);
-                let mut x = x as isize;
-                let mut y = y as isize;
-                let mut dir = dir;
-                loop {
-                    let (new_x, new_y) = match dir {
-                        Direction::Up => (x, y - 1),
-                        Direction::Down => (x, y + 1),
-                        Direction::Left => (x - 1, y),
-                        Direction::Right => (x + 1, y),
-                    };
-                    if new_x < 0
-                        || new_x >= grid.nrows() as isize
-                        || new_y < 0
-                        || new_y >= grid.ncols() as isize
-                    {
-                        break;
-                    }
-                    let cell = grid[(new_y as usize, new_x as usize)];
-                    if cell == Cell::Wall {
-                        dir = dir.turn_right();
-                    } else {
-                        x = new_x;
-                        y = new_y;
-                    }
-                    let already_visited =
-                        &mut visited_with_dir[(y as usize, x as usize)][dir.as_ordinal()];
-                    if *already_visited {
-                        looping_blocks += 1;
-                        break;
-                    }
-                    *already_visited = true;
-                }
+            if !leaves(grid, x, y, dir, visited_with_dir) {
+                looping_blocks += 1;
             }
             grid[(block_y, block_x)] = Cell::Empty;
         }
     }
 
-    looping_blocks
+    Ok(looping_blocks)
 }
 
-fn get_visited_cells(
-    grid: &DMatrix<Cell>,
+/// Fills `matrix` with `value`, only allocating if its shape changes.
+fn reset<T: nalgebra::Scalar + Copy>(
+    matrix: &mut DMatrix<T>,
+    nrows: usize,
+    ncols: usize,
+    value: T,
+) {
+    if matrix.shape() == (nrows, ncols) {
+        matrix.fill(value);
+    } else {
+        *matrix = DMatrix::from_element(nrows, ncols, value);
+    }
+}
+
+/// The guard's walk from `(x, y)`, yielding where the guard stands and faces
+/// after each step or turn.
+struct Walk<'a> {
+    grid: &'a DMatrix<Cell>,
     x: usize,
     y: usize,
-    mut dir: Direction,
-) -> DMatrix<bool> {
-    let mut visited = DMatrix::from_fn(grid.nrows(), grid.ncols(), |_, _| false);
-    let mut x = x as isize;
-    let mut y = y as isize;
-    loop {
-        let (new_x, new_y) = match dir {
-            Direction::Up => (x, y - 1),
-            Direction::Down => (x, y + 1),
-            Direction::Left => (x - 1, y),
-            Direction::Right => (x + 1, y),
-        };
-        if new_x < 0
-            || new_x >= grid.nrows() as isize
-            || new_y < 0
-            || new_y >= grid.ncols() as isize
-        {
-            break;
+    dir: Direction,
+    /// A walk off the map is in each cell facing each direction at most once,
+    /// so one that takes more steps than that is a loop.
+    steps_left: usize,
+    off_map: bool,
+}
+
+impl<'a> Walk<'a> {
+    fn new(grid: &'a DMatrix<Cell>, x: usize, y: usize, dir: Direction) -> Self {
+        Walk {
+            grid,
+            x,
+            y,
+            dir,
+            steps_left: 4 * grid.len(),
+            off_map: false,
         }
-        let cell = grid[(new_y as usize, new_x as usize)];
-        if cell == Cell::Wall {
-            dir = dir.turn_right();
+    }
+}
+
+impl Iterator for Walk<'_> {
+    type Item = (usize, usize, Direction);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.off_map || self.steps_left == 0 {
+            return None;
+        }
+        self.steps_left -= 1;
+        let (x, y) = (self.x, self.y);
+        let ahead = match self.dir {
+            Direction::Up => y.checked_sub(1).map(|y| (x, y)),
+            Direction::Down => Some((x, y + 1)),
+            Direction::Left => x.checked_sub(1).map(|x| (x, y)),
+            Direction::Right => Some((x + 1, y)),
+        };
+        let Some((x, y)) = ahead.filter(|&(x, y)| x < self.grid.ncols() && y < self.grid.nrows())
+        else {
+            self.off_map = true;
+            return None;
+        };
+        if self.grid[(y, x)] == Cell::Wall {
+            self.dir = self.dir.turn_right();
         } else {
-            x = new_x;
-            y = new_y;
+            (self.x, self.y) = (x, y);
+        }
+        Some((self.x, self.y, self.dir))
+    }
+}
+
+/// Whether the guard walks off the map rather than in a loop.
+fn leaves(
+    grid: &DMatrix<Cell>,
+    x: usize,
+    y: usize,
+    dir: Direction,
+    visited_with_dir: &mut DMatrix<[bool; 4]>,
+) -> bool {
+    reset(visited_with_dir, grid.nrows(), grid.ncols(), [false; 4]);
+    visited_with_dir[(y, x)][dir.as_ordinal()] = true;
+    let mut walk = Walk::new(grid, x, y, dir);
+    for (x, y, dir) in &mut walk {
+        let seen = &mut visited_with_dir[(y, x)][dir.as_ordinal()];
+        if *seen {
+            return false;
         }
-        visited[(y as usize, x as usize)] = true;
+        *seen = true;
     }
-    visited
+    walk.off_map
+}
+
+/// Marks every cell the guard walks onto, failing if the guard walks in a
+/// loop instead of off the map.
+fn get_visited_cells(
+    grid: &DMatrix<Cell>,
+    start_x: usize,
+    start_y: usize,
+    dir: Direction,
+    visited: &mut DMatrix<bool>,
+) -> Result<(), Error> {
+    reset(visited, grid.nrows(), grid.ncols(), false);
+    visited[(start_y, start_x)] = true;
+    let mut walk = Walk::new(grid, start_x, start_y, dir);
+    for (x, y, _) in &mut walk {
+        visited[(y, x)] = true;
+    }
+    if walk.off_map {
+        return Ok(());
+    }
+    Err(Error::Constraint(format!(
+        "line {}, column {}: the guard never leaves the map",
+        start_y + 1,
+        start_x + 1
+    )))
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -131,42 +383,47 @@ enum Cell {
     Wall,
 }
 
-fn parse_input(input: &str) -> (DMatrix<Cell>, usize, usize, Direction) {
+fn parse_input(input: &str, grid: &mut DMatrix<Cell>) -> Result<(usize, usize, Direction), Error> {
     let input = input.as_bytes();
+    let input = input.strip_suffix(b"\n").unwrap_or(input);
     let width = input.iter().take_while(|&&c| c != b'\n').count();
-    let height = input.len() / width;
-    let mut grid = DMatrix::from_fn(height, width, |_, _| Cell::Empty);
-    let mut guard_x = 0;
-    let mut guard_y = 0;
-    let mut guard_dir = Direction::Up;
-    for (y, line) in input.chunks(width + 1).enumerate() {
-        for (x, c) in line.iter().enumerate().take(width) {
-            match *c {
-                b'#' => grid[(y, x)] = Cell::Wall,
-                b'.' => grid[(y, x)] = Cell::Empty,
-                b'^' => {
-                    guard_x = x;
-                    guard_y = y;
-                    guard_dir = Direction::Up;
-                }
-                b'v' => {
-                    guard_x = x;
-                    guard_y = y;
-                    guard_dir = Direction::Down;
-                }
-                b'<' => {
-                    guard_x = x;
-                    guard_y = y;
-                    guard_dir = Direction::Left;
-                }
-                b'>' => {
-                    guard_x = x;
-                    guard_y = y;
-                    guard_dir = Direction::Right;
+    if width == 0 {
+        return Err(Error::parse(1, 1, "map"));
+    }
+    let height = input.len().div_ceil(width + 1);
+    reset(grid, height, width, Cell::Empty);
+    let mut guard = None;
+    for (y, line) in input.split(|&c| c == b'\n').enumerate() {
+        if line.len() != width {
+            let column = line.len().min(width) + 1;
+            return Err(Error::parse(y + 1, column, "row as wide as the first"));
+        }
+        for (x, c) in line.iter().enumerate() {
+            let dir = match *c {
+                b'#' => {
+                    grid[(y, x)] = Cell::Wall;
+                    continue;
                 }
-                c => panic!("invalid character {c}"),
+                b'.' => continue,
+                b'^' => Direction::Up,
+                b'v' => Direction::Down,
+                b'<' => Direction::Left,
+                b'>' => Direction::Right,
+                _ => return Err(Error::parse(y + 1, x + 1, "one of `.#^v<>`")),
+            };
+            if guard.is_some() {
+                return Err(Error::Constraint(format!(
+                    "line {}, column {}: second guard on the map",
+                    y + 1,
+                    x + 1
+                )));
             }
+            guard = Some((x, y, dir));
         }
     }
-    (grid, guard_x, guard_y, guard_dir)
+
+    let Some((guard_x, guard_y, guard_dir)) = guard else {
+        return Err(Error::Constraint("no guard on the map".to_string()));
+    };
+    Ok((guard_x, guard_y, guard_dir))
 }
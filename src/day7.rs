@@ -5,11 +5,196 @@ use std::simd::{
 
 use aoc_runner_derive::aoc;
 
+use crate::{validate::Finding, Error, Solution};
+
 const MAX_LINE_LENGTH: usize = 13;
 
+/// Any more and a number might not fit in a `u64`.
+const MAX_DIGITS: u8 = 19;
+
+const POWS: [u64; MAX_DIGITS as usize + 1] = {
+    let mut pows = [1; MAX_DIGITS as usize + 1];
+    let mut i = 1;
+    while i < pows.len() {
+        pows[i] = pows[i - 1] * 10;
+        i += 1;
+    }
+    pows
+};
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+    const NAME: &'static str = "Bridge Repair";
+
+    type Input = Vec<Equation>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+    type Scratch = Vec<Equation>;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        parse(input)
+    }
+
+    fn validate(input: &str) -> Vec<Finding> {
+        validate(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u64, Error> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Result<u64, Error> {
+        solve_part2(input)
+    }
+
+    fn part1_with(input: &str, scratch: &mut Vec<Equation>) -> Result<u64, Error> {
+        parse_into(input, scratch)?;
+        solve_part1(scratch)
+    }
+
+    fn part2_with(input: &str, scratch: &mut Vec<Equation>) -> Result<u64, Error> {
+        parse_into(input, scratch)?;
+        solve_part2(scratch)
+    }
+
+    fn solve_with(input: &str, scratch: &mut Vec<Equation>) -> Result<(u64, u64), Error> {
+        parse_into(input, scratch)?;
+        Ok((solve_part1(scratch)?, solve_part2(scratch)?))
+    }
+}
+
 #[aoc(day7, part1)]
 pub fn part1(input: &str) -> u64 {
-    partn::<Part1NextOp>(input)
+    try_part1(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+#[aoc(day7, part2)]
+pub fn part2(input: &str) -> u64 {
+    try_part2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_part1(input: &str) -> Result<u64, Error> {
+    solve_part1(&parse(input)?)
+}
+
+pub fn try_part2(input: &str) -> Result<u64, Error> {
+    solve_part2(&parse(input)?)
+}
+
+/// One calibration equation: the test value followed by its operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Equation {
+    nums: [u64; MAX_LINE_LENGTH],
+    digits: [u8; MAX_LINE_LENGTH],
+    count: usize,
+}
+
+pub fn parse(input: &str) -> Result<Vec<Equation>, Error> {
+    let mut equations = Vec::with_capacity(input.len() / 32);
+    parse_into(input, &mut equations)?;
+    Ok(equations)
+}
+
+/// Like [`parse`], but reuses the `equations` buffer.
+pub fn parse_into(input: &str, equations: &mut Vec<Equation>) -> Result<(), Error> {
+    equations.clear();
+    iter_lines(input, |nums, digits, count| {
+        equations.push(Equation {
+            nums,
+            digits,
+            count,
+        })
+    })
+}
+
+/// Reference for [`parse`], without the SIMD fast path. Both accept exactly
+/// the same inputs.
+pub fn parse_scalar(input: &str) -> Result<Vec<Equation>, Error> {
+    let mut equations = Vec::new();
+    iter_lines_scalar(input, 1, |nums, digits, count| {
+        equations.push(Equation {
+            nums,
+            digits,
+            count,
+        })
+    })?;
+    Ok(equations)
+}
+
+/// Reports every number that is not 1 to 19 digits, lines without `: ` after
+/// the test value or with more than 13 numbers, and the first line too long
+/// for the SIMD path, after which the rest is parsed without it.
+pub fn validate(input: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut simd = !cfg!(target_arch = "wasm32");
+    let mut offset = 0;
+    for (i, text) in input.split('\n').enumerate() {
+        let line = i + 1;
+        let start = offset;
+        offset += text.len() + 1;
+        if text.is_empty() {
+            continue;
+        }
+        // The SIMD path needs each line's newline within 64 bytes of its
+        // start, and leaves the last 64 bytes to the scalar path anyway.
+        if simd && input.len() - start >= 64 {
+            if text.len() >= 64 {
+                findings.push(Finding::slow_path(
+                    line,
+                    64,
+                    "line longer than 63 bytes, so the SIMD path gives up",
+                ));
+                simd = false;
+            }
+        } else {
+            simd = false;
+        }
+
+        let Some((target, operands)) = text.split_once(": ") else {
+            let column = text.bytes().take_while(u8::is_ascii_digit).count() + 1;
+            findings.push(Finding::malformed(
+                line,
+                column,
+                "expected `: ` after the test value",
+            ));
+            continue;
+        };
+        let mut column = 1;
+        let mut count = 0;
+        for number in std::iter::once(target).chain(operands.split(' ')) {
+            if let Some(offset) = number.bytes().position(|c| !c.is_ascii_digit()) {
+                findings.push(Finding::malformed(line, column + offset, "expected digit"));
+            } else if number.is_empty() || number.len() > MAX_DIGITS as usize {
+                findings.push(Finding::malformed(
+                    line,
+                    column,
+                    "expected number of at most 19 digits",
+                ));
+            }
+            count += 1;
+            if count == MAX_LINE_LENGTH + 1 {
+                findings.push(Finding::malformed(
+                    line,
+                    column,
+                    format!("more than {MAX_LINE_LENGTH} numbers"),
+                ));
+            }
+            column += number.len() + if count == 1 { 2 } else { 1 };
+        }
+    }
+    findings
+}
+
+/// Fails if the total calibration result does not fit in a `u64`.
+pub fn solve_part1(equations: &[Equation]) -> Result<u64, Error> {
+    partn::<Part1NextOp>(equations)
+}
+
+/// Fails if the total calibration result does not fit in a `u64`.
+pub fn solve_part2(equations: &[Equation]) -> Result<u64, Error> {
+    partn::<Part2NextOp>(equations)
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -47,7 +232,7 @@ impl NextOp for Part2NextOp {
 
 struct Ops<'a>(&'a mut [Op]);
 
-impl<'a> Ops<'a> {
+impl Ops<'_> {
     fn next<NO: NextOp>(&mut self) -> Option<usize> {
         let s = &mut self.0;
         let mut invalidated = 1;
@@ -63,21 +248,26 @@ impl<'a> Ops<'a> {
     }
 }
 
-#[aoc(day7, part2)]
-pub fn part2(input: &str) -> u64 {
-    partn::<Part2NextOp>(input)
-}
-
-fn partn<NO: NextOp>(input: &str) -> u64 {
-    let mut total = 0;
-    iter_lines(input, |nums, nums_digits, nums_count| {
+fn partn<NO: NextOp>(equations: &[Equation]) -> Result<u64, Error> {
+    let mut total = 0u128;
+    for &Equation {
+        nums,
+        digits: nums_digits,
+        count: nums_count,
+    } in equations
+    {
         let target = nums[0];
         let vars = &nums[2..nums_count];
         let digits = &nums_digits[2..nums_count];
         let mut ops = [Op::Add; MAX_LINE_LENGTH];
         let mut ops = Ops(&mut ops[2..nums_count]);
 
-        const POWS: [u64; 5] = [1, 10, 100, 1_000, 10_000];
+        if vars.is_empty() {
+            if nums[1] == target {
+                total += target as u128;
+            }
+            continue;
+        }
 
         let mut cache = [0; MAX_LINE_LENGTH];
         let mut valid = 0;
@@ -85,20 +275,22 @@ fn partn<NO: NextOp>(input: &str) -> u64 {
         loop {
             for i in valid..vars.len() {
                 let prev = if i == 0 { nums[1] } else { cache[i - 1] };
+                // Saturating is exact here: a saturated value can only equal
+                // the target again by multiplying with 0, which gives 0 either way.
                 match ops.0[i] {
-                    Op::Add => cache[i] = prev + vars[i],
-                    Op::Mul => cache[i] = prev * vars[i],
+                    Op::Add => cache[i] = prev.saturating_add(vars[i]),
+                    Op::Mul => cache[i] = prev.saturating_mul(vars[i]),
                     Op::Cons => {
-                        cache[i] = prev * POWS[digits[i] as usize];
-                        cache[i] += vars[i];
+                        cache[i] = prev.saturating_mul(POWS[digits[i] as usize]);
+                        cache[i] = cache[i].saturating_add(vars[i]);
                     }
                 }
             }
             valid = vars.len();
 
             if cache[vars.len() - 1] == target {
-                total += target;
-                return;
+                total += target as u128;
+                break;
             }
 
             if let Some(invalidated) = ops.next::<NO>() {
@@ -107,27 +299,43 @@ fn partn<NO: NextOp>(input: &str) -> u64 {
                 break;
             }
         }
-    });
-    total
+    }
+    u64::try_from(total)
+        .map_err(|_| Error::Constraint(format!("total calibration result {total} overflows")))
 }
 
 fn iter_lines(
     input: &str,
     mut handle_line: impl FnMut([u64; MAX_LINE_LENGTH], [u8; MAX_LINE_LENGTH], usize),
-) {
+) -> Result<(), Error> {
+    // Scalar on wasm32, see `crate::wasm`.
+    if cfg!(target_arch = "wasm32") {
+        return iter_lines_scalar(input, 1, handle_line);
+    }
+
+    let all_input = input;
     let mut input = input.as_bytes();
+    let mut line = 1;
 
     let newline = u8x64::splat(b'\n');
     let zero_ascii = u8x64::splat(b'0');
     let nine_ascii = u8x64::splat(b'9');
 
     while let Some(chunk) = input.array_chunks::<64>().next() {
-        let line = u8x64::from_array(*chunk);
-        let newline_mask = line.simd_eq(newline);
-        let newline_index = newline_mask.first_set().unwrap();
-        let digit_mask = line.simd_ge(zero_ascii) & line.simd_le(nine_ascii);
+        let line_bytes = u8x64::from_array(*chunk);
+        let newline_mask = line_bytes.simd_eq(newline);
+        let Some(newline_index) = newline_mask.first_set() else {
+            break;
+        };
+        let digit_mask = line_bytes.simd_ge(zero_ascii) & line_bytes.simd_le(nine_ascii);
 
-        let values = line - zero_ascii;
+        let values = line_bytes - zero_ascii;
+
+        if newline_index == 0 {
+            input = &input[1..];
+            line += 1;
+            continue;
+        }
 
         let mut nums = [0; MAX_LINE_LENGTH];
         let mut num_count = 0;
@@ -140,39 +348,96 @@ fn iter_lines(
             .enumerate()
             .take(newline_index + 1)
         {
+            let unexpected = |expected| Err(Error::parse(line, i + 1, expected));
             if is_digit {
+                if i > 0 && chunk[i - 1] == b':' {
+                    return unexpected("space");
+                }
+                if digit_count == MAX_DIGITS {
+                    return unexpected("number of at most 19 digits");
+                }
                 nums[num_count] *= 10;
                 nums[num_count] += values[i] as u64;
                 digit_count += 1;
+            } else if digit_count == 0 {
+                return unexpected("number");
             } else if is_first {
+                if chunk[i] != b':' {
+                    return unexpected("`:` after the test value");
+                }
                 is_first = false;
-            } else {
+            } else if i == newline_index || chunk[i] == b' ' {
+                if num_count + 1 == MAX_LINE_LENGTH && i != newline_index {
+                    return Err(too_many_numbers(line));
+                }
                 nums_digits[num_count] = digit_count;
                 digit_count = 0;
                 num_count += 1;
+            } else {
+                return unexpected("space");
             }
         }
 
+        if num_count < 2 {
+            return Err(Error::parse(line, newline_index + 1, "number"));
+        }
         handle_line(nums, nums_digits, num_count);
 
         input = &input[(newline_index + 1)..];
+        line += 1;
     }
 
-    let input = unsafe { std::str::from_utf8_unchecked(input) };
-    for line in input.lines() {
-        if line.is_empty() {
+    let rest = &all_input[(all_input.len() - input.len())..];
+    iter_lines_scalar(rest, line, handle_line)
+}
+
+fn iter_lines_scalar(
+    input: &str,
+    first_line: usize,
+    mut handle_line: impl FnMut([u64; MAX_LINE_LENGTH], [u8; MAX_LINE_LENGTH], usize),
+) -> Result<(), Error> {
+    for (line, text) in input
+        .split('\n')
+        .enumerate()
+        .map(|(i, text)| (first_line + i, text))
+    {
+        if text.is_empty() {
             continue;
         }
         let mut nums = [0; MAX_LINE_LENGTH];
         let mut nums_count = 0;
         let mut nums_digits = [0; MAX_LINE_LENGTH];
-        for part in line.split(' ') {
-            let part = part.trim_end_matches(':');
-            nums[nums_count] *= 10;
-            nums[nums_count] += part.parse::<u64>().unwrap();
+        let Some((target, operands)) = text.split_once(": ") else {
+            let column = text.bytes().take_while(u8::is_ascii_digit).count() + 1;
+            return Err(Error::parse(line, column, "`: ` after the test value"));
+        };
+        let mut column = 1;
+        for part in std::iter::once(target).chain(operands.split(' ')) {
+            if nums_count == MAX_LINE_LENGTH {
+                return Err(too_many_numbers(line));
+            }
+            if part.is_empty() || part.len() > MAX_DIGITS as usize {
+                return Err(Error::parse(line, column, "number of at most 19 digits"));
+            }
+            if let Some(offset) = part.bytes().position(|c| !c.is_ascii_digit()) {
+                return Err(Error::parse(line, column + offset, "digit"));
+            }
+            nums[nums_count] = part.bytes().fold(0, |n, c| n * 10 + (c - b'0') as u64);
             nums_digits[nums_count] = part.len() as u8;
             nums_count += 1;
+            column += part.len() + if nums_count == 1 { 2 } else { 1 };
+        }
+        if nums_count < 2 {
+            return Err(Error::parse(line, text.len() + 1, "number"));
         }
         handle_line(nums, nums_digits, nums_count);
     }
+
+    Ok(())
+}
+
+fn too_many_numbers(line: usize) -> Error {
+    Error::Constraint(format!(
+        "line {line} has more than {MAX_LINE_LENGTH} numbers"
+    ))
 }
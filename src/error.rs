@@ -0,0 +1,68 @@
+use std::fmt;
+
+use crate::day5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The input does not have the shape the puzzle describes. Lines and
+    /// columns start at 1.
+    Parse {
+        line: usize,
+        column: usize,
+        expected: &'static str,
+    },
+    /// The input has the right shape, but breaks an assumption the solver
+    /// relies on.
+    Constraint(String),
+}
+
+impl Error {
+    pub(crate) fn parse(line: usize, column: usize, expected: &'static str) -> Self {
+        Error::Parse {
+            line,
+            column,
+            expected,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse {
+                line,
+                column,
+                expected,
+            } => write!(f, "line {line}, column {column}: expected {expected}"),
+            Error::Constraint(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<day5::ParseError> for Error {
+    fn from(error: day5::ParseError) -> Self {
+        match error {
+            day5::ParseError::MissingSeparator { line } => {
+                Error::parse(line, 1, "blank line between rules and updates")
+            }
+            day5::ParseError::MalformedRule { line, column } => {
+                Error::parse(line, column, "rule `NN|NN`")
+            }
+            day5::ParseError::MalformedUpdate { line, column } => {
+                Error::parse(line, column, "update `NN,NN,...`")
+            }
+            day5::ParseError::PageOutOfRange { line, column, .. } => {
+                Error::parse(line, column, "two-digit page number")
+            }
+            day5::ParseError::UpdateTooLong { .. } => Error::Constraint(error.to_string()),
+        }
+    }
+}
+
+impl From<day5::OrderingConflict> for Error {
+    fn from(conflict: day5::OrderingConflict) -> Self {
+        Error::Constraint(conflict.to_string())
+    }
+}
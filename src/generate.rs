@@ -0,0 +1,347 @@
+//! Seeded random inputs that satisfy every assumption the solvers make.
+
+use std::fmt::Write;
+
+use crate::day5::{MAX_UPDATE_LEN, PAGES};
+
+/// splitmix64, which is plenty for making up puzzle inputs.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`, `n` must not be 0.
+    pub fn below(&mut self, n: u64) -> u64 {
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.below(high - low + 1)
+    }
+
+    /// True with probability `1 / n`.
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.below(n) == 0
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i as u64 + 1) as usize);
+        }
+    }
+}
+
+/// An input the size of the real puzzle input.
+pub fn puzzle_input(day: u8, seed: u64) -> Option<String> {
+    let rng = &mut Rng::new(seed);
+    Some(match day {
+        1 => day1(rng, 1000),
+        2 => day2(rng, 1000),
+        3 => day3(rng, 20000),
+        4 => day4(rng, 140, 140),
+        5 => day5(rng, 200),
+        6 => day6(rng, 130, 130),
+        7 => day7(rng, 850).input,
+        _ => return None,
+    })
+}
+
+/// Two columns of 5 digit location ids. About a third of the right column is
+/// copied from the left so the similarity score is not 0.
+pub fn day1(rng: &mut Rng, lines: usize) -> String {
+    let left: Vec<u64> = (0..lines).map(|_| rng.range(10000, 99999)).collect();
+    let mut input = String::with_capacity(lines * 14);
+    for &l in &left {
+        let r = if rng.one_in(3) {
+            *rng.pick(&left)
+        } else {
+            rng.range(10000, 99999)
+        };
+        writeln!(input, "{l}   {r}").unwrap();
+    }
+    input
+}
+
+/// Reports of 5 to 8 levels. Most are safe or one bad level away from it.
+pub fn day2(rng: &mut Rng, reports: usize) -> String {
+    let mut input = String::with_capacity(reports * 20);
+    for _ in 0..reports {
+        let len = rng.range(5, 8) as usize;
+        let mut levels = vec![rng.range(10, 90) as i64];
+        let direction = if rng.one_in(2) { 1 } else { -1 };
+        for _ in 1..len {
+            let step = direction * rng.range(1, 3) as i64;
+            levels.push((levels.last().unwrap() + step).clamp(1, 99));
+        }
+        match rng.below(3) {
+            0 => {}
+            1 => {
+                let i = rng.below(len as u64) as usize;
+                levels[i] = rng.range(1, 99) as i64;
+            }
+            _ => levels.iter_mut().for_each(|l| *l = rng.range(1, 99) as i64),
+        }
+
+        for (i, level) in levels.iter().enumerate() {
+            let separator = if i == 0 { "" } else { " " };
+            write!(input, "{separator}{level}").unwrap();
+        }
+        input.push('\n');
+    }
+    input
+}
+
+/// About `len` bytes of corrupted memory with `mul`, `do` and `don't`
+/// instructions, some of them subtly broken, among the noise.
+pub fn day3(rng: &mut Rng, len: usize) -> String {
+    const NOISE: &[u8] = b" !#$%&'()*+,-./:;<>?@[]^_{|}~0123456789muldont'why()what()";
+    const WORDS: &[&str] = &["select()", "when()", "from()", "who()", "how()", "where()"];
+
+    let mut input = String::with_capacity(len + 16);
+    while input.len() < len {
+        let (a, b) = (rng.range(0, 999), rng.range(0, 999));
+        match rng.below(16) {
+            0..=3 => write!(input, "mul({a},{b})").unwrap(),
+            4 => input.push_str("do()"),
+            5 => input.push_str("don't()"),
+            6 => write!(input, "mul({a}, {b})").unwrap(),
+            7 => write!(input, "mul[{a},{b})").unwrap(),
+            8 => write!(input, "mul({a},{b}").unwrap(),
+            9 => write!(input, "mul({}{a},{b})", rng.range(1, 9)).unwrap(),
+            10 => input.push_str(rng.pick::<&str>(WORDS)),
+            _ => input.push(*rng.pick(NOISE) as char),
+        }
+    }
+    input.push('\n');
+    input
+}
+
+/// A letter grid with `XMAS` planted in every direction and some `X-MAS`
+/// crosses.
+pub fn day4(rng: &mut Rng, width: usize, height: usize) -> String {
+    const DIRECTIONS: [(isize, isize); 8] = [
+        (1, 0),
+        (-1, 0),
+        (0, 1),
+        (0, -1),
+        (1, 1),
+        (-1, -1),
+        (1, -1),
+        (-1, 1),
+    ];
+
+    let mut grid: Vec<u8> = (0..width * height).map(|_| *rng.pick(b"XMAS")).collect();
+    if width >= 4 && height >= 4 {
+        for _ in 0..width * height / 40 {
+            let (dx, dy) = *rng.pick(&DIRECTIONS);
+            let x = rng.below(width as u64) as isize;
+            let y = rng.below(height as u64) as isize;
+            let (end_x, end_y) = (x + 3 * dx, y + 3 * dy);
+            if !(0..width as isize).contains(&end_x) || !(0..height as isize).contains(&end_y) {
+                continue;
+            }
+            for (i, &c) in b"XMAS".iter().enumerate() {
+                let i = i as isize;
+                grid[((y + i * dy) as usize) * width + (x + i * dx) as usize] = c;
+            }
+        }
+
+        for _ in 0..width * height / 80 {
+            let x = rng.range(1, width as u64 - 2) as usize;
+            let y = rng.range(1, height as u64 - 2) as usize;
+            let [a, b] = if rng.one_in(2) { *b"MS" } else { *b"SM" };
+            let [c, d] = if rng.one_in(2) { *b"MS" } else { *b"SM" };
+            grid[y * width + x] = b'A';
+            grid[(y - 1) * width + x - 1] = a;
+            grid[(y + 1) * width + x + 1] = b;
+            grid[(y + 1) * width + x - 1] = c;
+            grid[(y - 1) * width + x + 1] = d;
+        }
+    }
+
+    let mut input = String::with_capacity((width + 1) * height);
+    for row in grid.chunks(width) {
+        input.push_str(std::str::from_utf8(row).unwrap());
+        input.push('\n');
+    }
+    input
+}
+
+/// Rules ordering every pair of 49 pages consistently, followed by updates of
+/// odd length, about half of which are already in order.
+pub fn day5(rng: &mut Rng, updates: usize) -> String {
+    let mut order: Vec<u8> = PAGES.collect();
+    rng.shuffle(&mut order);
+    order.truncate(49);
+
+    let mut rules = Vec::with_capacity(order.len() * order.len() / 2);
+    for (i, &before) in order.iter().enumerate() {
+        for &after in &order[(i + 1)..] {
+            rules.push((before, after));
+        }
+    }
+    rng.shuffle(&mut rules);
+
+    let mut input = String::with_capacity(rules.len() * 6 + updates * 40);
+    for (before, after) in rules {
+        writeln!(input, "{before}|{after}").unwrap();
+    }
+    input.push('\n');
+
+    let rank = |page: &u8| order.iter().position(|p| p == page).unwrap();
+    for _ in 0..updates {
+        let len = 2 * rng.range(2, (MAX_UPDATE_LEN as u64 - 1) / 2) as usize + 1;
+        let mut update = order.clone();
+        rng.shuffle(&mut update);
+        update.truncate(len);
+        if rng.one_in(2) {
+            update.sort_by_key(rank);
+        }
+
+        let update: Vec<String> = update.iter().map(u8::to_string).collect();
+        input.push_str(&update.join(","));
+        input.push('\n');
+    }
+    input
+}
+
+/// A map with scattered obstructions and a guard who walks off of it
+/// eventually.
+pub fn day6(rng: &mut Rng, width: usize, height: usize) -> String {
+    loop {
+        let mut grid: Vec<u8> = (0..width * height)
+            .map(|_| if rng.one_in(20) { b'#' } else { b'.' })
+            .collect();
+        let guard = rng.below(grid.len() as u64) as usize;
+        let (direction, guard_char) = *rng.pick(&[(0, b'^'), (1, b'>'), (2, b'v'), (3, b'<')]);
+        grid[guard] = guard_char;
+
+        if guard_leaves(&grid, width, guard, direction) {
+            let mut input = String::with_capacity((width + 1) * height);
+            for row in grid.chunks(width) {
+                input.push_str(std::str::from_utf8(row).unwrap());
+                input.push('\n');
+            }
+            return input;
+        }
+    }
+}
+
+fn guard_leaves(grid: &[u8], width: usize, guard: usize, mut direction: usize) -> bool {
+    const STEPS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+    let height = grid.len() / width;
+    let mut seen = vec![0u8; grid.len()];
+    let (mut x, mut y) = ((guard % width) as isize, (guard / width) as isize);
+    loop {
+        let cell = y as usize * width + x as usize;
+        if seen[cell] & (1 << direction) != 0 {
+            return false;
+        }
+        seen[cell] |= 1 << direction;
+
+        let (dx, dy) = STEPS[direction];
+        let (next_x, next_y) = (x + dx, y + dy);
+        if !(0..width as isize).contains(&next_x) || !(0..height as isize).contains(&next_y) {
+            return true;
+        }
+        if grid[next_y as usize * width + next_x as usize] == b'#' {
+            direction = (direction + 1) % 4;
+        } else {
+            (x, y) = (next_x, next_y);
+        }
+    }
+}
+
+/// Calibration equations along with the answers they must produce.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calibration {
+    pub input: String,
+    pub part1: u64,
+    pub part2: u64,
+}
+
+/// Equations of 2 to 12 operands. About half are made by applying random
+/// operators, the rest have a test value one above the largest any choice of
+/// operators can reach, so cannot be solved.
+pub fn day7(rng: &mut Rng, equations: usize) -> Calibration {
+    let mut calibration = Calibration {
+        input: String::with_capacity(equations * 30),
+        part1: 0,
+        part2: 0,
+    };
+
+    for _ in 0..equations {
+        let (target, operands) = loop {
+            let operands: Vec<u64> = (0..rng.range(2, 12))
+                .map(|_| {
+                    let max = *rng.pick(&[9, 99, 999]);
+                    rng.range(1, max)
+                })
+                .collect();
+            let solvable = rng.one_in(2);
+            let target = operands[1..].iter().try_fold(operands[0], |acc, &n| {
+                let op = if solvable { rng.below(3) } else { 2 };
+                let value = match op {
+                    0 => acc.checked_add(n),
+                    1 => acc.checked_mul(n),
+                    _ => acc.checked_mul(10u64.pow(n.ilog10() + 1))?.checked_add(n),
+                }?;
+                (value < 1_000_000_000_000_000).then_some(value)
+            });
+            if let Some(target) = target {
+                // Concatenation never gives less than adding or multiplying,
+                // so concatenating everything is the largest reachable value.
+                break (if solvable { target } else { target + 1 }, operands);
+            }
+        };
+
+        if reachable(target, operands[0], &operands[1..], false) {
+            calibration.part1 += target;
+        }
+        if reachable(target, operands[0], &operands[1..], true) {
+            calibration.part2 += target;
+        }
+
+        write!(calibration.input, "{target}:").unwrap();
+        for operand in operands {
+            write!(calibration.input, " {operand}").unwrap();
+        }
+        calibration.input.push('\n');
+    }
+    calibration
+}
+
+/// Plain search over every choice of operators, as an oracle independent of
+/// the solver. Operands are never 0, so no operator makes a value smaller.
+fn reachable(target: u64, value: u64, operands: &[u64], concatenate: bool) -> bool {
+    let Some((&n, rest)) = operands.split_first() else {
+        return value == target;
+    };
+    if value > target {
+        return false;
+    }
+    let next = |value: Option<u64>| value.is_some_and(|v| reachable(target, v, rest, concatenate));
+    next(value.checked_add(n))
+        || next(value.checked_mul(n))
+        || (concatenate
+            && next(
+                value
+                    .checked_mul(10u64.pow(n.ilog10() + 1))
+                    .and_then(|v| v.checked_add(n)),
+            ))
+}
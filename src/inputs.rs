@@ -0,0 +1,216 @@
+//! Puzzle inputs, read from a cache directory of `dayN.txt` files and fetched
+//! from an Advent of Code compatible server when missing.
+//!
+//! [`Inputs::from_env`] caches in `input/2024`, or `AOC_INPUT_DIR`, and with
+//! the `fetch` feature fetches with the session token in `AOC_SESSION` from
+//! `https://adventofcode.com`, or `AOC_URL`.
+
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+pub const YEAR: u16 = 2024;
+pub const DIR: &str = "input/2024";
+pub const URL: &str = "https://adventofcode.com";
+
+#[derive(Debug)]
+pub enum InputError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// The input is not cached, and there is no way to fetch it.
+    Missing {
+        path: PathBuf,
+    },
+    /// The server answered with something other than the input.
+    Status {
+        url: String,
+        status: u16,
+    },
+    /// The server could not be reached.
+    Transport {
+        url: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            #[cfg(feature = "fetch")]
+            InputError::Missing { path } => write!(
+                f,
+                "{} does not exist; set AOC_SESSION to fetch it",
+                path.display()
+            ),
+            #[cfg(not(feature = "fetch"))]
+            InputError::Missing { path } => write!(
+                f,
+                "{} does not exist; fetching it needs `--features fetch`",
+                path.display()
+            ),
+            InputError::Status { url, status } => write!(f, "{url}: HTTP {status}"),
+            InputError::Transport { url, message } => write!(f, "{url}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Where inputs missing from the cache come from.
+pub trait Fetch {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, InputError>;
+}
+
+/// Fetches inputs from `{url}/{year}/day/{day}/input` with a session cookie.
+#[cfg(feature = "fetch")]
+pub struct Http {
+    pub(crate) url: String,
+    pub(crate) session: String,
+    pub(crate) agent: ureq::Agent,
+}
+
+#[cfg(feature = "fetch")]
+impl Http {
+    pub fn new(url: impl Into<String>, session: impl Into<String>) -> Self {
+        Http {
+            url: url.into().trim_end_matches('/').to_string(),
+            session: session.into(),
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!(
+                    env!("CARGO_PKG_NAME"),
+                    "/",
+                    env!("CARGO_PKG_VERSION")
+                ))
+                .build(),
+        }
+    }
+
+    /// `AOC_URL`, or [`URL`], with the `AOC_SESSION` token if one is set.
+    pub fn from_env() -> Option<Self> {
+        let session = std::env::var("AOC_SESSION").ok()?;
+        let url = std::env::var("AOC_URL").unwrap_or_else(|_| URL.to_string());
+        Some(Http::new(url, session.trim()))
+    }
+}
+
+#[cfg(feature = "fetch")]
+impl Fetch for Http {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, InputError> {
+        let url = format!("{}/{year}/day/{day}/input", self.url);
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call()
+            .map_err(|error| match error {
+                ureq::Error::Status(status, _) => InputError::Status {
+                    url: url.clone(),
+                    status,
+                },
+                ureq::Error::Transport(transport) => InputError::Transport {
+                    url: url.clone(),
+                    message: transport.to_string(),
+                },
+            })?;
+        response
+            .into_string()
+            .map_err(|error| InputError::Transport {
+                url,
+                message: error.to_string(),
+            })
+    }
+}
+
+pub struct Inputs<F = Box<dyn Fetch>> {
+    dir: PathBuf,
+    year: u16,
+    fetch: Option<F>,
+}
+
+impl Inputs {
+    /// Inputs cached in `AOC_INPUT_DIR` or [`DIR`], fetched with the
+    /// `AOC_SESSION` token when one is set.
+    pub fn from_env() -> Self {
+        let dir = std::env::var_os("AOC_INPUT_DIR").map_or_else(|| DIR.into(), PathBuf::from);
+        #[cfg(feature = "fetch")]
+        let fetch = Http::from_env().map(|http| Box::new(http) as Box<dyn Fetch>);
+        #[cfg(not(feature = "fetch"))]
+        let fetch = None;
+        Inputs {
+            dir,
+            year: YEAR,
+            fetch,
+        }
+    }
+
+    /// Inputs only ever read from `dir`.
+    pub fn offline(dir: impl Into<PathBuf>) -> Self {
+        Inputs {
+            dir: dir.into(),
+            year: YEAR,
+            fetch: None,
+        }
+    }
+}
+
+impl<F: Fetch> Inputs<F> {
+    pub fn new(dir: impl Into<PathBuf>, year: u16, fetch: F) -> Self {
+        Inputs {
+            dir: dir.into(),
+            year,
+            fetch: Some(fetch),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day{day}.txt"))
+    }
+
+    /// Reads the day's input from the cache, fetching and caching it first if
+    /// it is missing.
+    pub fn get(&self, day: u8) -> Result<String, InputError> {
+        let path = self.path(day);
+        match std::fs::read_to_string(&path) {
+            Ok(input) => return Ok(input),
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                return Err(InputError::Io { path, error })
+            }
+            Err(_) => {}
+        }
+        let Some(fetch) = &self.fetch else {
+            return Err(InputError::Missing { path });
+        };
+        let input = fetch.fetch(self.year, day)?;
+        write(&path, &input)?;
+        Ok(input)
+    }
+}
+
+impl<F: Fetch + ?Sized> Fetch for Box<F> {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, InputError> {
+        (**self).fetch(year, day)
+    }
+}
+
+/// Writes through a temporary file, so an interrupted write never leaves a
+/// truncated input in the cache.
+fn write(path: &Path, input: &str) -> Result<(), InputError> {
+    let io = |error| InputError::Io {
+        path: path.to_path_buf(),
+        error,
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(io)?;
+    }
+    let partial = path.with_extension("txt.partial");
+    std::fs::write(&partial, input).map_err(io)?;
+    std::fs::rename(&partial, path).map_err(io)
+}
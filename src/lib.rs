@@ -4,6 +4,8 @@
 
 use aoc_runner_derive::aoc_lib;
 
+pub mod allocator;
+pub mod answers;
 pub mod day1;
 pub mod day2;
 pub mod day3;
@@ -11,21 +13,48 @@ pub mod day4;
 pub mod day5;
 pub mod day6;
 pub mod day7;
+mod error;
+pub mod generate;
+pub mod inputs;
+mod solution;
+pub mod submit;
+pub mod validate;
+pub mod wasm;
+
+pub use error::Error;
+#[cfg(feature = "rayon")]
+pub use solution::solve_many_in;
+pub use solution::{solution, solve_many, Answers, DynSolution, Parsed, Solution, SOLUTIONS};
 
 aoc_lib! { year = 2024 }
 
 #[macro_export]
 macro_rules! benchmark {
-    ($($day:ident),*) => {
-        $(
-            use criterion::{criterion_group, criterion_main, Criterion};
-            const INPUT: &str = include_str!(concat!("../input/2024/", stringify!($day), ".txt"));
-            pub fn criterion_benchmark(c: &mut Criterion) {
-                c.bench_function(concat!(stringify!($day), " part 1"), |b| b.iter(|| aoc2024::$day::part1(INPUT)));
-                c.bench_function(concat!(stringify!($day), " part 2"), |b| b.iter(|| aoc2024::$day::part2(INPUT)));
-            }
-            criterion_group!(benches, criterion_benchmark);
-            criterion_main!(benches);
-        )*
+    ($day:ident :: $solution:ident $(: $($alternative:ident),+)?) => {
+        use aoc2024::Solution;
+        use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+        type S = aoc2024::$day::$solution;
+        #[global_allocator]
+        static GLOBAL: aoc2024::allocator::Allocator = aoc2024::allocator::ALLOCATOR;
+        pub fn criterion_benchmark(c: &mut Criterion) {
+            let input = aoc2024::inputs::Inputs::from_env()
+                .get(S::DAY)
+                .unwrap_or_else(|e| panic!("{e}"));
+            let input = input.as_str();
+            let parsed = S::parse(input).unwrap();
+            let mut group = c.benchmark_group(format!("day{}", S::DAY));
+            group.throughput(Throughput::Bytes(input.len() as u64));
+            group.bench_function("part 1", |b| b.iter(|| S::part1(&S::parse(input).unwrap())));
+            group.bench_function("part 2", |b| b.iter(|| S::part2(&S::parse(input).unwrap())));
+            group.bench_function("parse", |b| b.iter(|| S::parse(input)));
+            group.bench_function("solve part 1", |b| b.iter(|| S::part1(&parsed)));
+            group.bench_function("solve part 2", |b| b.iter(|| S::part2(&parsed)));
+            $($(
+                group.bench_function(stringify!($alternative), |b| b.iter(|| aoc2024::$day::$alternative(input)));
+            )+)?
+            group.finish();
+        }
+        criterion_group!(benches, criterion_benchmark);
+        criterion_main!(benches);
     };
 }
@@ -1,3 +1,476 @@
-use aoc_runner_derive::aoc_main;
+use std::{
+    fmt::{self, Display},
+    io::Read,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
 
-aoc_main! { lib = aoc2024 }
+use aoc2024::{
+    answers::{self, AnswerFile, Verdict},
+    inputs::Inputs,
+    validate::Kind,
+    DynSolution, Error, SOLUTIONS,
+};
+
+#[global_allocator]
+static GLOBAL: aoc2024::allocator::Allocator = aoc2024::allocator::ALLOCATOR;
+
+/// The usage message, with how missing inputs are fetched after `$inputs`.
+macro_rules! usage {
+    ($inputs:literal) => {
+        concat!(
+            "\
+usage: aoc2024 [OPTIONS] [DAY[:PART]...|all]
+
+Runs the given days and parts, or every one of them by default.
+
+options:
+    -i, --input PATH  read the puzzle input from PATH, or stdin if PATH is `-`.
+                      Only one day may be given. Defaults to dayN.txt in
+                      $AOC_INPUT_DIR or input/2024",
+            $inputs,
+            "
+        --json        print the answers as a JSON array
+        --table       print the answers as a table, with the total time
+        --verify      check the answers against the answers file, printing PASS,
+                      FAIL or MISSING for each part
+        --answers PATH
+                      the answers file for --verify. Defaults to
+                      input/2024/answers.toml
+        --validate    check each day's input against the assumptions its parser
+                      and solvers make, printing every broken one with its
+                      line and column instead of solving
+        --submit      submit the answer to the single DAY:PART given, with the
+                      $AOC_SESSION token. Guesses are kept in guesses.txt next
+                      to the inputs, and never sent twice
+    -p, --parallel    run every part at once, each on its own thread. Times
+                      are less accurate, but the total wall-clock time drops
+    -h, --help        print this message"
+        )
+    };
+}
+
+#[cfg(feature = "fetch")]
+const USAGE: &str = usage!(
+    ", fetched from $AOC_URL or
+                      adventofcode.com with the $AOC_SESSION token if missing"
+);
+#[cfg(not(feature = "fetch"))]
+const USAGE: &str = usage!(
+    ". Fetching missing inputs
+                      needs --features fetch"
+);
+
+struct Timed {
+    answer: String,
+    parse: Duration,
+    solve: Duration,
+}
+
+enum Failure {
+    Parse(Error),
+    Solve(Error),
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Parse(e @ Error::Parse { .. }) => write!(f, "parse error: {e}"),
+            Failure::Parse(e) => write!(f, "invalid input: {e}"),
+            Failure::Solve(e) => write!(f, "no answer: {e}"),
+        }
+    }
+}
+
+fn timed(solution: &dyn DynSolution, part: u8, input: &str) -> Result<Timed, Failure> {
+    let start = Instant::now();
+    let parsed = solution.parse(input).map_err(Failure::Parse)?;
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    let answer = solution.part(part, &parsed).map_err(Failure::Solve)?;
+    let solve = start.elapsed();
+
+    Ok(Timed {
+        answer,
+        parse,
+        solve,
+    })
+}
+
+struct Options {
+    /// `(day, part)` pairs in the order given, each only once.
+    selected: Vec<(u8, u8)>,
+    input: Option<String>,
+    json: bool,
+    table: bool,
+    verify: bool,
+    answers: Option<String>,
+    validate: bool,
+    submit: bool,
+    parallel: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        selected: Vec::new(),
+        input: None,
+        json: false,
+        table: false,
+        verify: false,
+        answers: None,
+        validate: false,
+        submit: false,
+        parallel: false,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(String::new()),
+            "--json" => options.json = true,
+            "--table" => options.table = true,
+            "--verify" => options.verify = true,
+            "--validate" => options.validate = true,
+            "--submit" => options.submit = true,
+            "--answers" => {
+                options.answers = Some(args.next().ok_or("missing path after --answers")?);
+            }
+            "-p" | "--parallel" => options.parallel = true,
+            "-i" | "--input" => {
+                options.input = Some(args.next().ok_or("missing path after --input")?);
+            }
+            "all" => {
+                for solution in SOLUTIONS {
+                    select(&mut options.selected, solution.day(), None);
+                }
+            }
+            _ => {
+                let (day, part) = match arg.split_once(':') {
+                    Some((day, part)) => (day, Some(part)),
+                    None => (arg.as_str(), None),
+                };
+                let day = day
+                    .trim_start_matches("day")
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|&day| aoc2024::solution(day).is_some())
+                    .ok_or(format!("unknown day `{arg}`"))?;
+                let part = part
+                    .map(|part| match part {
+                        "1" => Ok(1),
+                        "2" => Ok(2),
+                        _ => Err(format!("unknown part `{arg}`")),
+                    })
+                    .transpose()?;
+                select(&mut options.selected, day, part);
+            }
+        }
+    }
+
+    if options.selected.is_empty() {
+        for solution in SOLUTIONS {
+            select(&mut options.selected, solution.day(), None);
+        }
+    }
+    if [
+        options.json,
+        options.table,
+        options.verify,
+        options.validate,
+        options.submit,
+    ]
+    .into_iter()
+    .filter(|&mode| mode)
+    .count()
+        > 1
+    {
+        return Err(
+            "only one of --json, --table, --verify, --validate and --submit may be given"
+                .to_string(),
+        );
+    }
+    if options.submit && options.selected.len() != 1 {
+        return Err("--submit needs a single DAY:PART".to_string());
+    }
+    if options.answers.is_some() && !options.verify {
+        return Err("--answers only makes sense with --verify".to_string());
+    }
+    if options.verify && options.input.is_some() {
+        return Err("--verify checks the cached inputs, not --input".to_string());
+    }
+    if options.input.is_some() {
+        let first = options.selected[0].0;
+        if options.selected.iter().any(|&(day, _)| day != first) {
+            return Err("--input only makes sense for a single day".to_string());
+        }
+    }
+    Ok(options)
+}
+
+/// Adds the part, or both parts, of the day that are not selected yet.
+fn select(selected: &mut Vec<(u8, u8)>, day: u8, part: Option<u8>) {
+    for p in [1, 2] {
+        if part.is_none_or(|part| part == p) && !selected.contains(&(day, p)) {
+            selected.push((day, p));
+        }
+    }
+}
+
+fn read_input(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| format!("failed to read stdin: {e}"))?;
+        Ok(input)
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("failed to read {path}: {e}"))
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Submits `answer` unless earlier guesses settle it, returning whether it is
+/// correct.
+#[cfg(feature = "fetch")]
+fn submit(inputs: &Inputs, day: u8, part: u8, answer: &str) -> Result<bool, String> {
+    use aoc2024::submit::{Outcome, Submission, Submitter};
+
+    let http = aoc2024::inputs::Http::from_env().ok_or("set AOC_SESSION to submit answers")?;
+    let mut submitter = Submitter::new(
+        http,
+        aoc2024::inputs::YEAR,
+        inputs.dir().join("guesses.txt"),
+    )
+    .map_err(|e| e.to_string())?;
+    let (outcome, known) = match submitter
+        .submit(day, part, answer)
+        .map_err(|e| e.to_string())?
+    {
+        Submission::Posted(outcome) => (outcome, ""),
+        Submission::Known(outcome) => (outcome, " (from earlier guesses, not sent)"),
+    };
+    println!("Day {day} - Part {part}: {answer} is {outcome}{known}");
+    Ok(outcome == Outcome::Correct)
+}
+
+#[cfg(not(feature = "fetch"))]
+fn submit(_: &Inputs, _: u8, _: u8, _: &str) -> Result<bool, String> {
+    Err("submitting needs the `fetch` feature".to_string())
+}
+
+/// Runs every `(day, part, input)` job, in order or all at once, and returns
+/// the results in job order with the wall-clock time of the whole run.
+fn run(jobs: &[(u8, u8, &str)], parallel: bool) -> (Vec<Result<Timed, Failure>>, Duration) {
+    let job =
+        |&(day, part, input): &(u8, u8, &str)| timed(aoc2024::solution(day).unwrap(), part, input);
+    let start = Instant::now();
+    let results = if parallel {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = jobs.iter().map(|j| scope.spawn(move || job(j))).collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    } else {
+        jobs.iter().map(job).collect()
+    };
+    (results, start.elapsed())
+}
+
+fn print_table(jobs: &[(u8, u8, &str)], results: &[Result<Timed, Failure>], wall: Duration) {
+    let width = results
+        .iter()
+        .map(|result| result.as_ref().map_or(1, |timed| timed.answer.len()))
+        .chain(["Answer".len()])
+        .max()
+        .unwrap();
+    println!(
+        "Day  Part  {:<width$}  {:>10}  {:>10}  {:>10}",
+        "Answer", "Parse", "Solve", "Total"
+    );
+    let mut total = Duration::ZERO;
+    for (&(day, part, _), result) in jobs.iter().zip(results) {
+        match result {
+            Ok(timed) => {
+                total += timed.parse + timed.solve;
+                println!(
+                    "{day:>3}  {part:>4}  {:<width$}  {:>10.1?}  {:>10.1?}  {:>10.1?}",
+                    timed.answer,
+                    timed.parse,
+                    timed.solve,
+                    timed.parse + timed.solve,
+                );
+            }
+            Err(_) => println!("{day:>3}  {part:>4}  {:<width$}", "-"),
+        }
+    }
+    println!("Total: {total:.1?} ({wall:.1?} wall clock)");
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) if message.is_empty() => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let answer_file = if options.verify {
+        let path = options.answers.as_deref().unwrap_or(answers::PATH);
+        match read_input(path).and_then(|file| {
+            AnswerFile::parse(&file).map_err(|e| format!("failed to parse {path}: {e}"))
+        }) {
+            Ok(answer_file) => answer_file,
+            Err(message) => {
+                eprintln!("error: {message}");
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        AnswerFile::default()
+    };
+
+    let provider = Inputs::from_env();
+    let mut inputs: Vec<(u8, String)> = Vec::new();
+    for &(day, _) in &options.selected {
+        if inputs.iter().any(|(d, _)| *d == day) {
+            continue;
+        }
+        let input = match &options.input {
+            Some(path) => read_input(path),
+            None => provider.get(day).map_err(|e| e.to_string()),
+        };
+        match input {
+            Ok(input) => inputs.push((day, input)),
+            Err(message) => {
+                eprintln!("error: {message}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if options.validate {
+        let mut failed = false;
+        for (day, input) in &inputs {
+            let findings = aoc2024::solution(*day).unwrap().validate(input);
+            if findings.is_empty() {
+                println!("Day {day}: ok");
+            }
+            for finding in findings {
+                failed |= finding.kind != Kind::SlowPath;
+                println!("Day {day} - {finding}");
+            }
+        }
+        return if failed {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        };
+    }
+
+    let mut jobs = Vec::new();
+    for &(day, part) in &options.selected {
+        let input = &inputs.iter().find(|(d, _)| *d == day).unwrap().1;
+        jobs.push((day, part, input.as_str()));
+    }
+    let (results, wall) = run(&jobs, options.parallel);
+
+    let mut failed = false;
+    let mut json = Vec::new();
+    let mut verdicts = [0; 3];
+    for (&(day, p, _), result) in jobs.iter().zip(&results) {
+        match result {
+            Ok(timed) if options.json => json.push(format!(
+                "{{\"day\":{day},\"part\":{p},\"answer\":{},\"parse_ns\":{},\"solve_ns\":{}}}",
+                json_string(&timed.answer),
+                timed.parse.as_nanos(),
+                timed.solve.as_nanos(),
+            )),
+            Ok(_) if options.table => {}
+            Ok(timed) if options.submit => match submit(&provider, day, p, &timed.answer) {
+                Ok(correct) => failed |= !correct,
+                Err(message) => {
+                    failed = true;
+                    eprintln!("error: {message}");
+                }
+            },
+            Ok(timed) if options.verify => match answer_file.check(day, p, &timed.answer) {
+                Verdict::Pass => {
+                    verdicts[0] += 1;
+                    println!("Day {day} - Part {p}: PASS");
+                }
+                Verdict::Fail { expected } => {
+                    verdicts[1] += 1;
+                    failed = true;
+                    println!(
+                        "Day {day} - Part {p}: FAIL (got {}, expected {expected})",
+                        timed.answer
+                    );
+                }
+                Verdict::Missing => {
+                    verdicts[2] += 1;
+                    println!("Day {day} - Part {p}: MISSING (got {})", timed.answer);
+                }
+            },
+            Ok(timed) => {
+                println!("Day {day} - Part {p}: {}", timed.answer);
+                println!("\tparse: {:?}", timed.parse);
+                println!("\tsolve: {:?}", timed.solve);
+            }
+            Err(failure) => {
+                failed = true;
+                if options.verify {
+                    verdicts[1] += 1;
+                    println!("Day {day} - Part {p}: FAIL ({failure})");
+                    continue;
+                }
+                eprintln!("error: day {day} part {p}: {failure}");
+                if options.json {
+                    json.push(format!(
+                        "{{\"day\":{day},\"part\":{p},\"error\":{}}}",
+                        json_string(&failure.to_string()),
+                    ));
+                }
+            }
+        }
+    }
+
+    if options.table {
+        print_table(&jobs, &results, wall);
+    }
+    if options.verify {
+        let [passed, failed, missing] = verdicts;
+        println!("{passed} passed, {failed} failed, {missing} missing");
+    }
+    if options.json {
+        println!("[{}]", json.join(","));
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
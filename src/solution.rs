@@ -0,0 +1,150 @@
+use std::{any::Any, fmt::Display};
+
+use crate::{day1, day2, day3, day4, day5, day6, day7, validate::Finding, Error};
+
+/// One day's puzzle, split into a parse step shared by both parts and the two
+/// solvers.
+pub trait Solution {
+    const DAY: u8;
+    const NAME: &'static str;
+
+    type Input: Send + Sync + 'static;
+    type Answer1: Display;
+    type Answer2: Display;
+    /// Buffers that [`Solution::part1_with`] and [`Solution::part2_with`]
+    /// keep between calls.
+    type Scratch: Default;
+
+    fn parse(input: &str) -> Result<Self::Input, Error>;
+    /// Every assumption of the parser and solvers that `input` breaks.
+    fn validate(input: &str) -> Vec<Finding>;
+    fn part1(input: &Self::Input) -> Result<Self::Answer1, Error>;
+    fn part2(input: &Self::Input) -> Result<Self::Answer2, Error>;
+
+    /// Parses and solves part 1 without allocating, once `scratch` has grown
+    /// to fit inputs of this size.
+    fn part1_with(input: &str, scratch: &mut Self::Scratch) -> Result<Self::Answer1, Error>;
+    /// Parses and solves part 2 without allocating, once `scratch` has grown
+    /// to fit inputs of this size.
+    fn part2_with(input: &str, scratch: &mut Self::Scratch) -> Result<Self::Answer2, Error>;
+
+    /// Solves both parts with `scratch`. Days override this to parse once.
+    fn solve_with(
+        input: &str,
+        scratch: &mut Self::Scratch,
+    ) -> Result<(Self::Answer1, Self::Answer2), Error> {
+        Ok((
+            Self::part1_with(input, scratch)?,
+            Self::part2_with(input, scratch)?,
+        ))
+    }
+}
+
+/// Answers to both parts of one input.
+pub type Answers<S> = (<S as Solution>::Answer1, <S as Solution>::Answer2);
+
+/// Solves both parts of every input in order, reusing one scratch for all of
+/// them.
+pub fn solve_many<'a, S: Solution>(
+    inputs: impl IntoIterator<Item = &'a str>,
+) -> Vec<Result<Answers<S>, Error>> {
+    let mut scratch = S::Scratch::default();
+    inputs
+        .into_iter()
+        .map(|input| S::solve_with(input, &mut scratch))
+        .collect()
+}
+
+/// [`solve_many`] spread over `pool`, with a scratch per batch of inputs
+/// rather than per input. Answers are still in the order of `inputs`.
+#[cfg(feature = "rayon")]
+pub fn solve_many_in<S: Solution>(
+    pool: &rayon::ThreadPool,
+    inputs: &[impl AsRef<str> + Sync],
+) -> Vec<Result<Answers<S>, Error>>
+where
+    S::Answer1: Send,
+    S::Answer2: Send,
+{
+    use rayon::prelude::*;
+
+    pool.install(|| {
+        inputs
+            .par_iter()
+            .map_init(S::Scratch::default, |scratch, input| {
+                S::solve_with(input.as_ref(), scratch)
+            })
+            .collect()
+    })
+}
+
+/// Parsed input of some [`DynSolution`], only usable with that solution.
+pub struct Parsed(Box<dyn Any + Send + Sync>);
+
+/// [`Solution`] with its types erased, so every day fits in [`SOLUTIONS`].
+pub trait DynSolution: Sync {
+    fn day(&self) -> u8;
+    fn name(&self) -> &'static str;
+    fn parse(&self, input: &str) -> Result<Parsed, Error>;
+    fn validate(&self, input: &str) -> Vec<Finding>;
+    fn part1(&self, input: &Parsed) -> Result<String, Error>;
+    fn part2(&self, input: &Parsed) -> Result<String, Error>;
+
+    fn part(&self, part: u8, input: &Parsed) -> Result<String, Error> {
+        match part {
+            1 => self.part1(input),
+            2 => self.part2(input),
+            _ => Err(Error::Constraint(format!("there is no part {part}"))),
+        }
+    }
+}
+
+impl<S: Solution + Sync> DynSolution for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn name(&self) -> &'static str {
+        S::NAME
+    }
+
+    fn parse(&self, input: &str) -> Result<Parsed, Error> {
+        Ok(Parsed(Box::new(S::parse(input)?)))
+    }
+
+    fn validate(&self, input: &str) -> Vec<Finding> {
+        S::validate(input)
+    }
+
+    fn part1(&self, input: &Parsed) -> Result<String, Error> {
+        S::part1(downcast::<S>(input)).map(|answer| answer.to_string())
+    }
+
+    fn part2(&self, input: &Parsed) -> Result<String, Error> {
+        S::part2(downcast::<S>(input)).map(|answer| answer.to_string())
+    }
+}
+
+fn downcast<S: Solution>(input: &Parsed) -> &S::Input {
+    input
+        .0
+        .downcast_ref()
+        .unwrap_or_else(|| panic!("input was not parsed by day {}", S::DAY))
+}
+
+pub static SOLUTIONS: &[&dyn DynSolution] = &[
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+];
+
+pub fn solution(day: u8) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS
+        .iter()
+        .copied()
+        .find(|solution| solution.day() == day)
+}
@@ -0,0 +1,482 @@
+//! Submits answers to an Advent of Code compatible server, keeping every
+//! guess in a local file so the same wrong answer is never sent twice.
+//!
+//! The guesses file has a line per answer the server judged,
+//! `DAY<tab>PART<tab>ANSWER<tab>VERDICT`, where the verdict is one of
+//! `correct`, `incorrect`, `too high` or `too low`. Answers holding a tab or a
+//! line break are refused rather than written. Whenever the server asks for a
+//! wait, a `wait until<tab>SECONDS` line keeps when it ends, in seconds since
+//! the Unix epoch, and nothing is posted before then.
+
+use std::{
+    fmt, io,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+/// What the server made of an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    /// Wrong, and no more answers are accepted for `wait`.
+    Incorrect {
+        hint: Option<Hint>,
+        wait: Option<Duration>,
+    },
+    /// Sent before the wait from the last wrong answer was over, so the
+    /// answer was not checked.
+    TooSoon {
+        wait: Duration,
+    },
+    /// The part is solved already, or its first part is not.
+    WrongLevel,
+    /// Anything else, with the text of the response.
+    Unrecognized(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::Incorrect { hint, wait } => {
+                match hint {
+                    Some(Hint::TooHigh) => write!(f, "too high")?,
+                    Some(Hint::TooLow) => write!(f, "too low")?,
+                    None => write!(f, "incorrect")?,
+                }
+                match wait {
+                    Some(wait) => write!(f, ", wait {wait:?} before trying again"),
+                    None => Ok(()),
+                }
+            }
+            Outcome::TooSoon { wait } => write!(f, "answered too recently, wait {wait:?}"),
+            Outcome::WrongLevel => write!(f, "already solved, or part 1 is not"),
+            Outcome::Unrecognized(text) => write!(f, "unrecognized response: {text}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// A line of the guesses file that does not parse. Lines start at 1.
+    Guesses {
+        path: PathBuf,
+        line: usize,
+    },
+    /// An answer with a tab or a line break, which the guesses file cannot
+    /// keep.
+    Answer(String),
+    /// A wait the server asked for that ends past what the clock can hold.
+    Wait(Duration),
+    Status {
+        url: String,
+        status: u16,
+    },
+    Transport {
+        url: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubmitError::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            SubmitError::Guesses { path, line } => {
+                write!(
+                    f,
+                    "{}: line {line}: expected DAY, PART, ANSWER and VERDICT, \
+                     or `wait until` and SECONDS",
+                    path.display()
+                )
+            }
+            SubmitError::Answer(answer) => {
+                write!(f, "answer {answer:?} has a tab or a line break")
+            }
+            SubmitError::Wait(wait) => {
+                write!(f, "the server asked to wait {wait:?}, which never ends")
+            }
+            SubmitError::Status { url, status } => write!(f, "{url}: HTTP {status}"),
+            SubmitError::Transport { url, message } => write!(f, "{url}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {}
+
+/// Where answers go, returning the body of the response.
+pub trait Post {
+    fn post(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<String, SubmitError>;
+}
+
+#[cfg(feature = "fetch")]
+impl Post for crate::inputs::Http {
+    fn post(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<String, SubmitError> {
+        let url = format!("{}/{year}/day/{day}/answer", self.url);
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .map_err(|error| match error {
+                ureq::Error::Status(status, _) => SubmitError::Status {
+                    url: url.clone(),
+                    status,
+                },
+                ureq::Error::Transport(transport) => SubmitError::Transport {
+                    url: url.clone(),
+                    message: transport.to_string(),
+                },
+            })?;
+        response
+            .into_string()
+            .map_err(|error| SubmitError::Transport {
+                url,
+                message: error.to_string(),
+            })
+    }
+}
+
+impl<P: Post + ?Sized> Post for &P {
+    fn post(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<String, SubmitError> {
+        (**self).post(year, day, part, answer)
+    }
+}
+
+/// Reads the outcome from the `<article>` of a response page.
+pub fn parse_response(html: &str) -> Outcome {
+    let article = html
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(html, |(article, _)| article);
+    let text = strip_tags(article);
+
+    if text.contains("That's the right answer") {
+        Outcome::Correct
+    } else if text.contains("That's not the right answer") {
+        let hint = if text.contains("too high") {
+            Some(Hint::TooHigh)
+        } else if text.contains("too low") {
+            Some(Hint::TooLow)
+        } else {
+            None
+        };
+        let wait = text
+            .split_once("lease wait ")
+            .and_then(|(_, rest)| rest.split_once(" before"))
+            .and_then(|(wait, _)| parse_wait(wait));
+        Outcome::Incorrect { hint, wait }
+    } else if text.contains("You gave an answer too recently") {
+        match text
+            .split_once("You have ")
+            .and_then(|(_, rest)| rest.split_once(" left to wait"))
+            .and_then(|(wait, _)| parse_wait(wait))
+        {
+            Some(wait) => Outcome::TooSoon { wait },
+            None => Outcome::Unrecognized(text),
+        }
+    } else if text.contains("You don't seem to be solving the right level") {
+        Outcome::WrongLevel
+    } else {
+        Outcome::Unrecognized(text)
+    }
+}
+
+/// Removes tags and squeezes whitespace, leaving the text of some HTML.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if in_tag => {}
+            c if c.is_whitespace() => {
+                if !text.ends_with(' ') {
+                    text.push(' ');
+                }
+            }
+            c => text.push(c),
+        }
+    }
+    text.trim().to_string()
+}
+
+/// Parses `one minute`, `5 minutes`, `37s` or `1m 5s`.
+fn parse_wait(wait: &str) -> Option<Duration> {
+    let mut words = wait.split_whitespace();
+    let mut seconds = 0u64;
+    while let Some(word) = words.next() {
+        let count = match word {
+            "a" | "an" | "one" => Some(1),
+            _ => word.parse::<u64>().ok(),
+        };
+        let (count, unit) = match count {
+            Some(count) => match words.next()?.trim_end_matches('s') {
+                "second" => (count, 1),
+                "minute" => (count, 60),
+                "hour" => (count, 3600),
+                _ => return None,
+            },
+            None => {
+                let unit = word.chars().last()?;
+                let count = word.strip_suffix(unit)?.parse::<u64>().ok()?;
+                match unit {
+                    's' => (count, 1),
+                    'm' => (count, 60),
+                    'h' => (count, 3600),
+                    _ => return None,
+                }
+            }
+        };
+        seconds = count
+            .checked_mul(unit)
+            .and_then(|wait| seconds.checked_add(wait))?;
+    }
+    (seconds > 0).then(|| Duration::from_secs(seconds))
+}
+
+/// An answer the server judged, as kept in the guesses file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Guess {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub correct: bool,
+    pub hint: Option<Hint>,
+}
+
+impl Guess {
+    fn outcome(&self) -> Outcome {
+        if self.correct {
+            Outcome::Correct
+        } else {
+            Outcome::Incorrect {
+                hint: self.hint,
+                wait: None,
+            }
+        }
+    }
+}
+
+/// Whether the answer went to the server, or was settled by earlier guesses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Submission {
+    Posted(Outcome),
+    Known(Outcome),
+}
+
+pub struct Submitter<P> {
+    post: P,
+    year: u16,
+    path: PathBuf,
+    guesses: Vec<Guess>,
+    wait_until: Option<SystemTime>,
+}
+
+impl<P: Post> Submitter<P> {
+    /// Loads the guesses kept at `path`, which need not exist yet.
+    pub fn new(post: P, year: u16, path: impl Into<PathBuf>) -> Result<Self, SubmitError> {
+        let path = path.into();
+        let (guesses, wait_until) = match std::fs::read_to_string(&path) {
+            Ok(file) => parse_guesses(&path, &file)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => (Vec::new(), None),
+            Err(error) => return Err(SubmitError::Io { path, error }),
+        };
+        Ok(Submitter {
+            post,
+            year,
+            path,
+            guesses,
+            wait_until,
+        })
+    }
+
+    pub fn guesses(&self) -> &[Guess] {
+        &self.guesses
+    }
+
+    /// When the last wait the server asked for ends, if it ever asked.
+    pub fn wait_until(&self) -> Option<SystemTime> {
+        self.wait_until
+    }
+
+    /// How much of the last wait the server asked for is left, in whole
+    /// seconds.
+    fn wait_left(&self) -> Option<Duration> {
+        let left = self.wait_until?.duration_since(SystemTime::now()).ok()?;
+        Some(Duration::from_secs(
+            left.as_secs() + u64::from(left.subsec_nanos() > 0),
+        ))
+    }
+
+    /// What the earlier guesses say about `answer`, if anything: the same
+    /// answer was judged before, the part is solved already, or a number is
+    /// out of the bounds earlier hints gave.
+    pub fn known(&self, day: u8, part: u8, answer: &str) -> Option<Outcome> {
+        let guesses = || {
+            self.guesses
+                .iter()
+                .filter(move |guess| (guess.day, guess.part) == (day, part))
+        };
+        if let Some(guess) = guesses().find(|guess| guess.answer == answer) {
+            return Some(guess.outcome());
+        }
+        if guesses().any(|guess| guess.correct) {
+            return Some(Outcome::WrongLevel);
+        }
+        let answer: i128 = answer.parse().ok()?;
+        guesses().find_map(|guess| {
+            let guessed: i128 = guess.answer.parse().ok()?;
+            let hint = guess.hint?;
+            let out_of_bounds = match hint {
+                Hint::TooHigh => answer >= guessed,
+                Hint::TooLow => answer <= guessed,
+            };
+            out_of_bounds.then_some(Outcome::Incorrect {
+                hint: Some(hint),
+                wait: None,
+            })
+        })
+    }
+
+    /// Posts `answer` unless earlier guesses settle it or the server asked to
+    /// wait, and keeps the verdict and any new wait the server gave.
+    pub fn submit(&mut self, day: u8, part: u8, answer: &str) -> Result<Submission, SubmitError> {
+        if answer.contains(['\t', '\n', '\r']) {
+            return Err(SubmitError::Answer(answer.to_string()));
+        }
+        if let Some(outcome) = self.known(day, part, answer) {
+            return Ok(Submission::Known(outcome));
+        }
+        if let Some(wait) = self.wait_left() {
+            return Ok(Submission::Known(Outcome::TooSoon { wait }));
+        }
+        let outcome = parse_response(&self.post.post(self.year, day, part, answer)?);
+        let guess = match &outcome {
+            Outcome::Correct => Some((true, None)),
+            &Outcome::Incorrect { hint, .. } => Some((false, hint)),
+            _ => None,
+        };
+        if let Some((correct, hint)) = guess {
+            self.record(Guess {
+                day,
+                part,
+                answer: answer.to_string(),
+                correct,
+                hint,
+            })?;
+        }
+        if let Outcome::Incorrect {
+            wait: Some(wait), ..
+        }
+        | Outcome::TooSoon { wait } = outcome
+        {
+            self.wait(wait)?;
+        }
+        Ok(Submission::Posted(outcome))
+    }
+
+    fn record(&mut self, guess: Guess) -> Result<(), SubmitError> {
+        let verdict = match (guess.correct, guess.hint) {
+            (true, _) => "correct",
+            (false, None) => "incorrect",
+            (false, Some(Hint::TooHigh)) => "too high",
+            (false, Some(Hint::TooLow)) => "too low",
+        };
+        self.append(&format!(
+            "{}\t{}\t{}\t{verdict}",
+            guess.day, guess.part, guess.answer
+        ))?;
+        self.guesses.push(guess);
+        Ok(())
+    }
+
+    /// Keeps that nothing is to be posted for `wait` from now.
+    fn wait(&mut self, wait: Duration) -> Result<(), SubmitError> {
+        let seconds = SystemTime::now()
+            .checked_add(wait)
+            .ok_or(SubmitError::Wait(wait))?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64()
+            .ceil() as u64;
+        let wait_until = UNIX_EPOCH
+            .checked_add(Duration::from_secs(seconds))
+            .ok_or(SubmitError::Wait(wait))?;
+        self.append(&format!("wait until\t{seconds}"))?;
+        self.wait_until = Some(wait_until);
+        Ok(())
+    }
+
+    fn append(&self, line: &str) -> Result<(), SubmitError> {
+        let io = |error| SubmitError::Io {
+            path: self.path.clone(),
+            error,
+        };
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(io)?;
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{line}"))
+            .map_err(io)
+    }
+}
+
+/// The guesses in the file, and when the last wait it keeps ends.
+fn parse_guesses(path: &Path, file: &str) -> Result<(Vec<Guess>, Option<SystemTime>), SubmitError> {
+    let mut guesses = Vec::new();
+    let mut wait_until = None;
+    for (i, line) in file.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let malformed = || SubmitError::Guesses {
+            path: path.to_path_buf(),
+            line: i + 1,
+        };
+        if let Some(seconds) = line.strip_prefix("wait until\t") {
+            let seconds = seconds.parse().map_err(|_| malformed())?;
+            wait_until = Some(
+                UNIX_EPOCH
+                    .checked_add(Duration::from_secs(seconds))
+                    .ok_or_else(malformed)?,
+            );
+            continue;
+        }
+        let mut fields = line.split('\t');
+        let mut field = || fields.next().ok_or_else(malformed);
+        let day = field()?.parse().map_err(|_| malformed())?;
+        let part = field()?.parse().map_err(|_| malformed())?;
+        let answer = field()?.to_string();
+        let (correct, hint) = match field()? {
+            "correct" => (true, None),
+            "incorrect" => (false, None),
+            "too high" => (false, Some(Hint::TooHigh)),
+            "too low" => (false, Some(Hint::TooLow)),
+            _ => return Err(malformed()),
+        };
+        guesses.push(Guess {
+            day,
+            part,
+            answer,
+            correct,
+            hint,
+        });
+    }
+    Ok((guesses, wait_until))
+}
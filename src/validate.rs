@@ -0,0 +1,85 @@
+//! Checks inputs against the assumptions each day's parser and solvers make,
+//! reporting every one that is broken instead of stopping at the first.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    /// The parser rejects the input.
+    Malformed,
+    /// The input parses, but a solver fails on it or never finishes.
+    Unsolvable,
+    /// The input is fine, but the fast path gives up on it and a slower
+    /// parser takes over.
+    SlowPath,
+}
+
+/// A broken assumption. Lines and columns start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub line: usize,
+    pub column: usize,
+    pub kind: Kind,
+    pub message: String,
+}
+
+impl Finding {
+    pub(crate) fn malformed(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Finding {
+            line,
+            column,
+            kind: Kind::Malformed,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn unsolvable(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Finding {
+            line,
+            column,
+            kind: Kind::Unsolvable,
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn slow_path(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Finding {
+            line,
+            column,
+            kind: Kind::SlowPath,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::Malformed => write!(f, "malformed"),
+            Kind::Unsolvable => write!(f, "unsolvable"),
+            Kind::SlowPath => write!(f, "slow path"),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}: {}",
+            self.line, self.column, self.kind, self.message
+        )
+    }
+}
+
+/// Numbered lines, without the empty one after a trailing newline.
+pub(crate) fn lines(input: &str) -> impl Iterator<Item = (usize, &[u8])> {
+    let input = input.as_bytes();
+    input
+        .strip_suffix(b"\n")
+        .unwrap_or(input)
+        .split(|&c| c == b'\n')
+        .enumerate()
+        .map(|(i, text)| (i + 1, text))
+        .filter(move |_| !input.is_empty())
+}
@@ -0,0 +1,34 @@
+//! The entry point of the WebAssembly build.
+//!
+//! `cargo build-wasm` builds the library for `wasm32-unknown-unknown`, where
+//! [`solve`] is exported through `wasm-bindgen`: JavaScript sees
+//! `solve(day, part, input)` returning the answer as a string, and throwing
+//! the error message if there is none. Everywhere else it is a plain function,
+//! so `cargo test-wasm` runs it and the rest of the tests on `wasm32-wasip1`
+//! under `wasmtime`.
+//!
+//! Both leave out the default features, since wasm32 has no jemalloc, threads
+//! for rayon, or sockets for fetching inputs. Days with a scalar fallback
+//! use it, as `std::simd` has no 64 byte vectors on wasm32 and splits them
+//! into slow scalar code.
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{solution, Error};
+
+/// Solves one part of a day, returning the answer or why there is none.
+#[cfg_attr(all(target_arch = "wasm32", target_os = "unknown"), wasm_bindgen)]
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, String> {
+    let solution = solution(day).ok_or_else(|| format!("there is no day {day}"))?;
+    if !(1..=2).contains(&part) {
+        return Err(format!("there is no part {part}"));
+    }
+    let parsed = solution.parse(input).map_err(|e| match e {
+        Error::Parse { .. } => format!("parse error: {e}"),
+        Error::Constraint(_) => format!("invalid input: {e}"),
+    })?;
+    solution
+        .part(part, &parsed)
+        .map_err(|e| format!("no answer: {e}"))
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt::Debug,
+};
+
+use aoc2024::*;
+use common::per_day_tests;
+
+/// Counts allocations made by the current thread, so tests running in
+/// parallel do not see each other's.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+/// Warms the scratch up on a generated input, then solves the real one.
+fn check<S: Solution>()
+where
+    S::Answer1: PartialEq + Debug,
+    S::Answer2: PartialEq + Debug,
+{
+    // On wasm32 the regex's lazy DFA still grows the first time it meets a
+    // state the warm-up input did not reach.
+    if cfg!(target_arch = "wasm32") && S::DAY == 3 {
+        return;
+    }
+
+    let inputs = common::inputs(S::DAY, 0..1);
+    let mut scratch = S::Scratch::default();
+    S::part1_with(&inputs[0], &mut scratch).unwrap();
+    S::part2_with(&inputs[0], &mut scratch).unwrap();
+    S::solve_with(&inputs[0], &mut scratch).unwrap();
+
+    let (parsed, count) = allocations(|| S::parse(&inputs[1]).unwrap());
+    assert_ne!(count, 0, "day {} is not counted", S::DAY);
+    let (part1, count) = allocations(|| S::part1_with(&inputs[1], &mut scratch).unwrap());
+    assert_eq!(count, 0, "day {} part 1", S::DAY);
+    assert_eq!(part1, S::part1(&parsed).unwrap(), "day {} part 1", S::DAY);
+
+    let (part2, count) = allocations(|| S::part2_with(&inputs[1], &mut scratch).unwrap());
+    assert_eq!(count, 0, "day {} part 2", S::DAY);
+    assert_eq!(part2, S::part2(&parsed).unwrap(), "day {} part 2", S::DAY);
+
+    let (answers, count) = allocations(|| S::solve_with(&inputs[1], &mut scratch).unwrap());
+    assert_eq!(count, 0, "day {} both parts", S::DAY);
+    assert_eq!(answers, (part1, part2), "day {} both parts", S::DAY);
+}
+
+per_day_tests!(check);
@@ -0,0 +1,43 @@
+use aoc2024::answers::{AnswerFile, AnswerFileError, Verdict};
+
+#[test]
+fn integers_and_strings() {
+    let file =
+        AnswerFile::parse("[day1]\npart1 = 11\npart2 = \"31\"\n\n[day7]\npart1 = 3749\n").unwrap();
+    assert_eq!(file.get(1, 1), Some("11"));
+    assert_eq!(file.get(1, 2), Some("31"));
+    assert_eq!(file.get(7, 1), Some("3749"));
+    assert_eq!(file.get(7, 2), None);
+}
+
+#[test]
+fn verdicts() {
+    let file = AnswerFile::parse("[day1]\npart1 = 11\n").unwrap();
+    assert_eq!(file.check(1, 1, "11"), Verdict::Pass);
+    assert_eq!(
+        file.check(1, 1, "12"),
+        Verdict::Fail {
+            expected: "11".to_string()
+        }
+    );
+    assert_eq!(file.check(1, 2, "31"), Verdict::Missing);
+}
+
+#[test]
+fn malformed() {
+    assert!(matches!(
+        AnswerFile::parse("[day1\n"),
+        Err(AnswerFileError::Toml(_))
+    ));
+    for (file, bad_key) in [
+        ("[first]\npart1 = 1\n", "first"),
+        ("day1 = 1\n", "day1"),
+        ("[day1]\npart3 = 1\n", "day1.part3"),
+        ("[day1]\npart1 = 1.5\n", "day1.part1"),
+    ] {
+        match AnswerFile::parse(file) {
+            Err(AnswerFileError::Entry { key, .. }) => assert_eq!(key, bad_key),
+            other => panic!("{file:?} gave {other:?}"),
+        }
+    }
+}
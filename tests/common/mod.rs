@@ -0,0 +1,76 @@
+//! Helpers shared by the integration tests, each of which uses only some.
+#![allow(dead_code, unused_imports, unused_macros)]
+
+use std::{
+    ops::{Deref, Range},
+    path::{Path, PathBuf},
+};
+
+use aoc2024::generate;
+
+/// An empty directory for one test, removed again when dropped, even if the
+/// test panics.
+pub fn temp_dir(name: &str) -> TempDir {
+    let dir = std::env::temp_dir().join(format!("aoc2024-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    TempDir(dir)
+}
+
+pub struct TempDir(PathBuf);
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        self
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// An example input from the puzzle text, in `input/2024/examples`.
+pub fn example(name: &str) -> String {
+    std::fs::read_to_string(format!("input/2024/examples/{name}.txt")).unwrap()
+}
+
+/// Generated inputs for `seeds`, with the real input after the first half of
+/// them, so scratch shrinks and grows between them.
+pub fn inputs(day: u8, seeds: Range<u64>) -> Vec<String> {
+    let mut inputs: Vec<String> = seeds
+        .map(|seed| generate::puzzle_input(day, seed).unwrap())
+        .collect();
+    inputs.insert(
+        inputs.len().div_ceil(2),
+        aoc2024::inputs::Inputs::from_env().get(day).unwrap(),
+    );
+    inputs
+}
+
+/// A test per day, each calling `$check::<DayN>()`.
+macro_rules! per_day_tests {
+    ($check:ident) => {
+        per_day_tests!($check:
+            day1 Day1, day2 Day2, day3 Day3, day4 Day4, day5 Day5, day6 Day6, day7 Day7
+        );
+    };
+    ($check:ident: $($day:ident $solution:ident),*) => {
+        $(
+            #[test]
+            fn $day() {
+                $check::<aoc2024::$day::$solution>();
+            }
+        )*
+    };
+}
+
+pub(crate) use per_day_tests;
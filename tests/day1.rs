@@ -0,0 +1,138 @@
+use aoc2024::{day1, validate::Kind, Error};
+use nalgebra::DMatrix;
+
+#[test]
+fn negative_ids() {
+    let input = "-3 4\n4 -3\n2 5\n-1 3\n3 -9\n3 3\n";
+    // Sorted: -3 -1 2 3 3 4 against -9 -3 3 3 4 5, 6 + 2 + 1 + 0 + 1 + 1 apart.
+    assert_eq!(day1::try_part1(input), Ok(11));
+    assert_eq!(day1::try_part2(input), Ok(-3 + 3 * 2 * 2 + 4));
+}
+
+#[test]
+fn crlf_line_endings() {
+    let input = "3   4\r\n4   3\r\n2   5\r\n1   3\r\n3   9\r\n3   3\r\n";
+    assert_eq!(day1::try_part1(input), Ok(11));
+    assert_eq!(day1::try_part2(input), Ok(31));
+}
+
+#[test]
+fn mixed_widths() {
+    let input = "3 4\n40000   3\n2\t\t5\n1      300\n  3 9\n3 3  \n";
+    // Sorted: 1 2 3 3 3 40000 against 3 3 4 5 9 300.
+    assert_eq!(day1::try_part1(input), Ok(2 + 1 + 1 + 2 + 6 + 39700));
+    assert_eq!(day1::try_part2(input), Ok(3 * 2 * 3));
+}
+
+#[test]
+fn extreme_ids() {
+    let input = "9223372036854775807 -9223372036854775808\n";
+    assert_eq!(
+        day1::try_part1(input),
+        Err(Error::Constraint(
+            "total distance 18446744073709551615 overflows".to_string()
+        ))
+    );
+    assert_eq!(day1::try_part2(input), Ok(0));
+
+    let input = "9223372036854775807 9223372036854775807\n0 9223372036854775807\n";
+    assert_eq!(day1::try_part1(input), Ok(9223372036854775807));
+    assert_eq!(
+        day1::try_part2(input),
+        Err(Error::Constraint(
+            "similarity score 18446744073709551614 overflows".to_string()
+        ))
+    );
+
+    let input = "-9223372036854775808 -9223372036854775807\n";
+    assert_eq!(day1::try_part1(input), Ok(1));
+}
+
+#[test]
+fn three_columns() {
+    let columns = day1::parse_columns("3 4 3\n4 3 3\n2 5 9\n").unwrap();
+    assert_eq!(columns, [[3, 4, 2], [4, 3, 5], [3, 3, 9]]);
+    // Sorted: 2 3 4, 3 4 5 and 3 3 9.
+    assert_eq!(
+        day1::distance_matrix(&columns),
+        Ok(DMatrix::from_row_slice(3, 3, &[0, 3, 6, 3, 0, 5, 6, 5, 0]))
+    );
+    assert_eq!(
+        day1::similarity_matrix(&columns),
+        Ok(DMatrix::from_row_slice(
+            3,
+            3,
+            &[9, 7, 6, 7, 12, 6, 6, 6, 21]
+        ))
+    );
+}
+
+#[test]
+fn empty_input() {
+    // The fixed-width path takes the first, the any-width path the others.
+    for input in ["", "\n", "  \n\n"] {
+        let columns = day1::parse_columns(input).unwrap();
+        assert_eq!(columns, Vec::<Vec<i64>>::new(), "{input:?}");
+        assert_eq!(
+            day1::similarity_matrix(&columns),
+            Ok(DMatrix::from_row_slice(0, 0, &[])),
+            "{input:?}"
+        );
+        assert_eq!(day1::try_part1(input), Ok(0), "{input:?}");
+        assert_eq!(day1::try_part2(input), Ok(0), "{input:?}");
+        assert!(
+            day1::validate(input)
+                .iter()
+                .all(|finding| finding.kind == Kind::SlowPath),
+            "{input:?}"
+        );
+    }
+}
+
+#[test]
+fn columns_of_different_lengths() {
+    let error = Err(Error::Constraint(
+        "column 2 has 1 location ids, but column 1 has 2".to_string(),
+    ));
+    let columns = [vec![1, 2], vec![3], vec![4, 5]];
+    assert_eq!(day1::distance_matrix(&columns), error);
+    assert_eq!(day1::similarity_matrix(&columns), error);
+}
+
+#[test]
+fn radix_and_counting_handle_any_input() {
+    for input in [
+        "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n",
+        "-3 4\n4 -3\n2 5\n-1 3\n3 -9\n3 3\n",
+        "3   4\r\n4   3\r\n2   5\r\n1   3\r\n3   9\r\n3   3\r\n",
+        "3 4\n40000   3\n2\t\t5\n1      300\n  3 9\n3 3  \n",
+        // Too wide a range of ids to count in a table.
+        "5 5\n-4000000 5\n5 -4000000\n4000000 4000000\n",
+        "9223372036854775807 -9223372036854775808\n",
+        "9223372036854775807 9223372036854775807\n0 9223372036854775807\n",
+        "",
+    ] {
+        assert_eq!(
+            day1::try_part1_radix(input),
+            day1::try_part1(input),
+            "{input}"
+        );
+        assert_eq!(
+            day1::try_part2_counting(input),
+            day1::try_part2(input),
+            "{input}"
+        );
+    }
+    assert_eq!(
+        day1::try_part2_counting("5 5\n-4000000 5\n5 -4000000\n4000000 4000000\n"),
+        Ok(5 * 2 * 2 - 4000000 + 4000000)
+    );
+    assert_eq!(
+        day1::try_part1_radix("1 2\n3 x\n"),
+        Err(Error::Parse {
+            line: 2,
+            column: 3,
+            expected: "location id"
+        })
+    );
+}
@@ -0,0 +1,42 @@
+use aoc2024::day2;
+
+#[test]
+fn fewer_than_eight_reports() {
+    assert_eq!(day2::part1("7 6 4 2 1\n1 3 6 7 9\n"), 2);
+    assert_eq!(day2::part2("7 67\n"), 1);
+}
+
+#[test]
+fn single_level_reports() {
+    assert_eq!(day2::part1("5\n8\n"), 2);
+    assert_eq!(day2::part2("5\n8\n"), 2);
+}
+
+#[test]
+fn empty_reports() {
+    let input = "\n8 8 9\n\n9 7 6 2 1\n1 2 3\n";
+    assert_eq!(day2::part1(input), 1);
+    assert_eq!(day2::part2(input), 2);
+}
+
+#[test]
+fn scalar_matches_vectorized() {
+    for input in [
+        "7 6 4 2 1\n1 3 6 7 9\n",
+        "5\n8\n",
+        "\n8 8 9\n\n9 7 6 2 1\n1 2 3\n",
+        "1 2 3 4 5 6 7 8\n8 7 6 5 4 3 2 9\n1 9 2 3 4 5 6 7\n",
+    ] {
+        let parsed = day2::parse(input).unwrap();
+        assert_eq!(
+            day2::solve_part1_scalar(&parsed),
+            day2::part1(input),
+            "{input:?}"
+        );
+        assert_eq!(
+            day2::solve_part2_scalar(&parsed),
+            day2::part2(input),
+            "{input:?}"
+        );
+    }
+}
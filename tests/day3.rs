@@ -0,0 +1,10 @@
+use aoc2024::day3;
+
+#[test]
+fn operands_have_at_most_three_digits() {
+    assert_eq!(day3::part1("mul(123,4)mul(1000,2)mul(1,2345)"), 492);
+    assert_eq!(
+        day3::part2("mul(0012,3)don't()mul(5,5)do()mul(999,999)"),
+        998001
+    );
+}
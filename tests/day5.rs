@@ -0,0 +1,285 @@
+use aoc2024::day5::{self, InvalidUpdate, OrderingConflict, PageOutOfRange, ParseError, Violation};
+
+fn orderings(rules: &[(u8, u8)]) -> day5::Orderings {
+    let mut orderings = day5::Orderings::new();
+    for &(before, after) in rules {
+        orderings.add_rule(before, after).unwrap();
+    }
+    orderings
+}
+
+#[test]
+fn topological_order_follows_the_rules() {
+    let orderings = orderings(&[(11, 22), (22, 33), (11, 33)]);
+    assert_eq!(
+        day5::topological_order(&[33, 11, 22], &orderings),
+        Ok(vec![11, 22, 33])
+    );
+}
+
+#[test]
+fn topological_order_cycle() {
+    let orderings = orderings(&[(11, 22), (22, 33), (33, 11)]);
+    let conflict = day5::topological_order(&[11, 22, 33], &orderings).unwrap_err();
+    assert_eq!(conflict, OrderingConflict::Cycle(vec![22, 33, 11]));
+    assert_eq!(
+        conflict.to_string(),
+        "rules form a cycle: 22 -> 33 -> 11 -> 22"
+    );
+}
+
+#[test]
+fn topological_order_ambiguous_pair() {
+    let orderings = orderings(&[(11, 22), (11, 33)]);
+    let conflict = day5::topological_order(&[33, 22, 11], &orderings).unwrap_err();
+    assert_eq!(conflict, OrderingConflict::Ambiguous(vec![(33, 22)]));
+    assert_eq!(conflict.to_string(), "no rule orders pages 33 and 22");
+}
+
+#[test]
+fn topological_order_duplicate_page() {
+    let orderings = orderings(&[]);
+    assert_eq!(
+        day5::topological_order(&[11, 11], &orderings),
+        Err(OrderingConflict::Ambiguous(vec![(11, 11)]))
+    );
+}
+
+fn parse_error(input: &str) -> ParseError {
+    let error = day5::parse_scalar(input).unwrap_err();
+    assert_eq!(input.parse::<day5::Input>().unwrap_err(), error);
+    error
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(parse_error(""), ParseError::MissingSeparator { line: 1 });
+    assert_eq!(
+        parse_error("11|22\n22|33\n"),
+        ParseError::MissingSeparator { line: 3 }
+    );
+    assert_eq!(
+        parse_error("11|22\n22-33\n\n11,22\n"),
+        ParseError::MalformedRule { line: 2, column: 3 }
+    );
+    assert_eq!(
+        parse_error("11|22\n\n22,,11\n"),
+        ParseError::MalformedUpdate { line: 3, column: 4 }
+    );
+    assert_eq!(
+        parse_error("11|100\n\n11\n"),
+        ParseError::PageOutOfRange {
+            line: 1,
+            column: 4,
+            page: 100
+        }
+    );
+    assert_eq!(
+        parse_error("11|22\n\n11,05\n"),
+        ParseError::PageOutOfRange {
+            line: 3,
+            column: 4,
+            page: 5
+        }
+    );
+    let long = ["11"; day5::MAX_UPDATE_LEN + 1].join(",");
+    assert_eq!(
+        parse_error(&format!("11|22\n\n11,22\n{long}\n")),
+        ParseError::UpdateTooLong { line: 4 }
+    );
+}
+
+#[test]
+fn parse_error_messages() {
+    assert_eq!(
+        ParseError::MissingSeparator { line: 1 }.to_string(),
+        "line 1: missing blank line between rules and updates"
+    );
+    assert_eq!(
+        ParseError::PageOutOfRange {
+            line: 1,
+            column: 4,
+            page: 100
+        }
+        .to_string(),
+        "line 1, column 4: page 100 is not between 10 and 99"
+    );
+    assert_eq!(
+        ParseError::UpdateTooLong { line: 4 }.to_string(),
+        format!(
+            "line 4: update has more than {} pages",
+            day5::MAX_UPDATE_LEN
+        )
+    );
+}
+
+#[test]
+fn crlf_line_endings() {
+    let lf = std::fs::read_to_string("input/2024/examples/day5.txt").unwrap();
+    let crlf = lf.replace('\n', "\r\n");
+    assert_eq!(day5::parse_scalar(&crlf), day5::parse_scalar(&lf));
+    assert_eq!(day5::try_part1(&crlf), Ok(143));
+    assert_eq!(day5::try_part2(&crlf), Ok(123));
+    assert_eq!(day5::validate(&crlf), day5::validate(&lf));
+}
+
+#[test]
+fn explain_invalid_updates() {
+    let input = day5::parse("47|53\n97|13\n47|53\n\n47,53\n53,47,13\n").unwrap();
+    let violations = day5::explain_update(&[53, 47, 13], &input);
+    assert_eq!(
+        violations,
+        [
+            Violation::BrokenRule {
+                before: 47,
+                before_index: 1,
+                after: 53,
+                after_index: 0,
+                rule_line: 1,
+            },
+            Violation::Unordered {
+                first: 47,
+                first_index: 1,
+                second: 13,
+                second_index: 2,
+            },
+        ]
+    );
+
+    let invalid = day5::explain_invalid_updates(&input);
+    assert_eq!(
+        invalid,
+        [InvalidUpdate {
+            line: 6,
+            pages: vec![53, 47, 13],
+            violations,
+        }]
+    );
+    assert_eq!(
+        invalid[0].to_string(),
+        "update on line 6 is invalid:\n  \
+         page 47 (index 1) must come before page 53 (index 0) per rule `47|53` on line 1\n  \
+         no rule orders page 47 (index 1) and page 13 (index 2)"
+    );
+}
+
+#[test]
+fn orderings_display_round_trips() {
+    let orderings = orderings(&[(97, 13), (47, 53), (13, 47), (97, 47)]);
+    assert_eq!(orderings.to_string(), "13|47\n47|53\n97|13\n97|47\n");
+    let input: day5::Input = format!("{orderings}\n97,13\n").parse().unwrap();
+    assert_eq!(input.orderings(), &orderings);
+}
+
+#[test]
+fn add_rule_rejects_pages_out_of_range() {
+    let mut orderings = day5::Orderings::new();
+    assert_eq!(orderings.add_rule(9, 22), Err(PageOutOfRange(9)));
+    assert_eq!(orderings.add_rule(22, 100), Err(PageOutOfRange(100)));
+    assert_eq!(
+        PageOutOfRange(100).to_string(),
+        "page 100 is not between 10 and 99"
+    );
+    assert_eq!(orderings, day5::Orderings::new());
+    assert_eq!(orderings.add_rule(10, 99), Ok(()));
+    assert!(orderings.must_precede(10, 99));
+}
+
+#[test]
+fn fix_update() {
+    let orderings = orderings(&[(11, 22), (22, 33), (11, 33)]);
+    let mut update = [33, 11, 22];
+    assert_eq!(day5::fix_update(&mut update, &orderings), Ok(()));
+    assert_eq!(update, [11, 22, 33]);
+
+    let mut update = [33, 44, 11];
+    assert_eq!(
+        day5::fix_update(&mut update, &orderings),
+        Err(OrderingConflict::Ambiguous(vec![(33, 44), (44, 11)]))
+    );
+    assert_eq!(update, [33, 44, 11]);
+}
+
+#[test]
+fn closure_is_transitive() {
+    let orderings = orderings(&[(11, 22), (22, 33)]);
+    let closure = orderings.closure();
+    assert!(closure.precedes(11, 33));
+    assert!(!closure.precedes(33, 11));
+    assert!(closure.is_ordered(33, 11));
+    assert!(!orderings.must_precede(11, 33));
+    assert_eq!(closure.predecessors(33).collect::<Vec<_>>(), [11, 22]);
+    assert_eq!(closure.successors(11).collect::<Vec<_>>(), [22, 33]);
+    assert!(closure.is_acyclic());
+    assert_eq!(closure.find_cycle(), None);
+}
+
+#[test]
+fn closure_self_loop() {
+    let orderings = orderings(&[(11, 11), (22, 33)]);
+    let closure = orderings.closure();
+    assert!(!closure.is_acyclic());
+    assert_eq!(closure.cyclic_pages().collect::<Vec<_>>(), [11]);
+    assert_eq!(closure.find_cycle(), Some(vec![11]));
+}
+
+#[test]
+fn closure_three_cycle() {
+    let orderings = orderings(&[(11, 22), (22, 33), (33, 11), (33, 44)]);
+    let closure = orderings.closure();
+    assert!(!closure.is_acyclic());
+    assert_eq!(closure.cyclic_pages().collect::<Vec<_>>(), [11, 22, 33]);
+    assert_eq!(closure.find_cycle(), Some(vec![22, 33, 11]));
+    assert!(closure.precedes(22, 22));
+    assert_eq!(closure.predecessors(44).collect::<Vec<_>>(), [11, 22, 33]);
+}
+
+#[test]
+fn pages_out_of_range() {
+    let orderings = orderings(&[(11, 22), (22, 33), (11, 33)]);
+    let closure = orderings.closure();
+    for page in [0, 100, 200] {
+        assert!(!orderings.must_precede(page, 11));
+        assert!(!orderings.must_precede(11, page));
+        assert!(!closure.precedes(page, 33));
+        assert!(!closure.precedes(11, page));
+        assert_eq!(closure.successors(page).count(), 0);
+        assert_eq!(closure.predecessors(page).count(), 0);
+
+        let mut update = [33, page, 11];
+        assert_eq!(
+            day5::is_update_valid(&update, &orderings),
+            Err(PageOutOfRange(page))
+        );
+        assert_eq!(
+            day5::topological_order(&update, &orderings),
+            Err(OrderingConflict::OutOfRange(PageOutOfRange(page)))
+        );
+        assert_eq!(
+            day5::fix_update(&mut update, &orderings),
+            Err(OrderingConflict::OutOfRange(PageOutOfRange(page)))
+        );
+        assert_eq!(update, [33, page, 11]);
+    }
+    let input = day5::parse("22|11\n\n11,22\n").unwrap();
+    assert_eq!(
+        day5::explain_update(&[200, 11, 22], &input),
+        [
+            Violation::BrokenRule {
+                before: 22,
+                before_index: 2,
+                after: 11,
+                after_index: 1,
+                rule_line: 1,
+            },
+            Violation::Unordered {
+                first: 200,
+                first_index: 0,
+                second: 11,
+                second_index: 1,
+            },
+        ]
+    );
+    assert_eq!(day5::is_update_valid(&[11, 22, 33], &orderings), Ok(true));
+    assert_eq!(day5::is_update_valid(&[33, 11], &orderings), Ok(false));
+}
@@ -0,0 +1,25 @@
+use aoc2024::{day6, wasm, Error};
+
+/// The guard turns right at each `#` and ends up back where it started.
+const LOOP: &str = ".#...\n....#\n#....\n.^.#.\n.....\n";
+
+#[test]
+fn guard_in_a_loop_is_an_error() {
+    let error = Error::Constraint("line 4, column 2: the guard never leaves the map".to_string());
+    assert_eq!(day6::try_part1(LOOP), Err(error.clone()));
+    assert_eq!(day6::try_part2(LOOP), Err(error.clone()));
+    assert_eq!(wasm::solve(6, 1, LOOP), Err(format!("no answer: {error}")));
+}
+
+#[test]
+fn maps_need_not_be_square() {
+    // Wider than tall: the guard walks off the right edge, past the row count.
+    assert_eq!(day6::try_part1("......\n>.....\n......\n"), Ok(6));
+    // Taller than wide: the guard walks off the bottom, past the column count.
+    assert_eq!(day6::try_part1("...\n.v.\n...\n...\n...\n...\n"), Ok(5));
+    // The loop from `LOOP` with a wall missing, which is the only obstruction
+    // that traps the guard again.
+    let wide = ".#......\n....#...\n........\n.^.#....\n........\n";
+    assert_eq!(day6::try_part1(wide), Ok(8));
+    assert_eq!(day6::try_part2(wide), Ok(1));
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use std::fmt::Debug;
+
+use aoc2024::*;
+use common::example;
+
+fn check<S: Solution>(name: &str, part1: S::Answer1, part2: S::Answer2)
+where
+    S::Answer1: PartialEq + Debug,
+    S::Answer2: PartialEq + Debug,
+{
+    let input = S::parse(&example(name)).unwrap();
+    assert_eq!(S::part1(&input).unwrap(), part1, "{name} part 1");
+    assert_eq!(S::part2(&input).unwrap(), part2, "{name} part 2");
+}
+
+#[test]
+fn day1() {
+    check::<day1::Day1>("day1", 11, 31);
+    assert_eq!(day1::part1_radix(&example("day1")), 11);
+    assert_eq!(day1::part2_counting(&example("day1")), 31);
+}
+
+#[test]
+fn day2() {
+    check::<day2::Day2>("day2", 2, 4);
+}
+
+#[test]
+fn day3() {
+    assert_eq!(day3::part1(&example("day3")), 161);
+    assert_eq!(day3::part2(&example("day3-part2")), 48);
+}
+
+#[test]
+fn day4() {
+    check::<day4::Day4>("day4", 18, 9);
+}
+
+#[test]
+fn day5() {
+    check::<day5::Day5>("day5", 143, 123);
+}
+
+#[test]
+fn day6() {
+    check::<day6::Day6>("day6", 41, 6);
+}
+
+#[test]
+fn day7() {
+    check::<day7::Day7>("day7", 3749, 11387);
+}
@@ -0,0 +1,244 @@
+//! Checks the solvers against generated inputs, with plain oracles written
+//! straight from the puzzle text rather than from the solvers.
+
+use std::collections::HashSet;
+
+use aoc2024::{
+    generate::{self, Rng},
+    *,
+};
+
+const SEEDS: std::ops::Range<u64> = 0..4;
+
+fn lines(input: &str) -> impl Iterator<Item = &str> {
+    input.lines().filter(|line| !line.is_empty())
+}
+
+#[test]
+fn day1() {
+    for seed in SEEDS {
+        let input = generate::puzzle_input(1, seed).unwrap();
+        let (mut left, mut right): (Vec<i64>, Vec<i64>) = lines(&input)
+            .map(|line| {
+                let (l, r) = line.split_once("   ").unwrap();
+                (l.parse::<i64>().unwrap(), r.parse::<i64>().unwrap())
+            })
+            .unzip();
+        left.sort();
+        right.sort();
+        let distance = left.iter().zip(&right).map(|(l, r)| (l - r).abs()).sum();
+        let similarity = left
+            .iter()
+            .map(|l| l * right.iter().filter(|r| *r == l).count() as i64)
+            .sum();
+        assert_eq!(day1::try_part1(&input), Ok(distance), "seed {seed}");
+        assert_eq!(day1::try_part2(&input), Ok(similarity), "seed {seed}");
+    }
+}
+
+#[test]
+fn day2() {
+    let safe = |levels: &[i64]| {
+        let steps: Vec<i64> = levels.windows(2).map(|w| w[1] - w[0]).collect();
+        steps.iter().all(|step| (1..=3).contains(step))
+            || steps.iter().all(|step| (-3..=-1).contains(step))
+    };
+    for seed in SEEDS {
+        let input = generate::puzzle_input(2, seed).unwrap();
+        let reports: Vec<Vec<i64>> = lines(&input)
+            .map(|line| line.split(' ').map(|l| l.parse().unwrap()).collect())
+            .collect();
+        let part1 = reports.iter().filter(|report| safe(report)).count() as u64;
+        let part2 = reports
+            .iter()
+            .filter(|report| {
+                (0..report.len()).any(|i| {
+                    let mut dampened = report.to_vec();
+                    dampened.remove(i);
+                    safe(&dampened)
+                })
+            })
+            .count() as u64;
+        assert_eq!(day2::try_part1(&input), Ok(part1), "seed {seed}");
+        assert_eq!(day2::try_part2(&input), Ok(part2), "seed {seed}");
+
+        let parsed = day2::parse(&input).unwrap();
+        assert_eq!(day2::solve_part1_scalar(&parsed), part1, "seed {seed}");
+        assert_eq!(day2::solve_part2_scalar(&parsed), part2, "seed {seed}");
+    }
+}
+
+/// The product of the `mul(X,Y)` starting at `text`, if it is one.
+fn mul(text: &str) -> Option<u64> {
+    let rest = text.strip_prefix("mul(")?;
+    let (a, rest) = rest.split_at(rest.find(|c: char| !c.is_ascii_digit())?);
+    let rest = rest.strip_prefix(',')?;
+    let (b, rest) = rest.split_at(rest.find(|c: char| !c.is_ascii_digit())?);
+    rest.strip_prefix(')')?;
+    if !(1..=3).contains(&a.len()) || !(1..=3).contains(&b.len()) {
+        return None;
+    }
+    Some(a.parse::<u64>().ok()? * b.parse::<u64>().ok()?)
+}
+
+#[test]
+fn day3() {
+    for seed in SEEDS {
+        let input = generate::puzzle_input(3, seed).unwrap();
+        let (mut part1, mut part2) = (0, 0);
+        let mut enabled = true;
+        for i in 0..input.len() {
+            let text = &input[i..];
+            if text.starts_with("do()") {
+                enabled = true;
+            } else if text.starts_with("don't()") {
+                enabled = false;
+            } else if let Some(product) = mul(text) {
+                part1 += product;
+                if enabled {
+                    part2 += product;
+                }
+            }
+        }
+        assert_eq!(day3::try_part1(&input), Ok(part1), "seed {seed}");
+        assert_eq!(day3::try_part2(&input), Ok(part2), "seed {seed}");
+    }
+}
+
+#[test]
+fn day4() {
+    for seed in SEEDS {
+        let input = generate::puzzle_input(4, seed).unwrap();
+        let grid: Vec<&[u8]> = lines(&input).map(str::as_bytes).collect();
+        let at = |x: isize, y: isize| {
+            let row = grid.get(usize::try_from(y).ok()?)?;
+            row.get(usize::try_from(x).ok()?).copied()
+        };
+
+        let mut part1 = 0;
+        let mut part2 = 0;
+        for y in 0..grid.len() as isize {
+            for x in 0..grid[0].len() as isize {
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if (0..4).all(|i| at(x + i * dx, y + i * dy) == Some(b"XMAS"[i as usize])) {
+                            part1 += 1;
+                        }
+                    }
+                }
+
+                let diagonal =
+                    |a, b| matches!((a, b), (Some(b'M'), Some(b'S')) | (Some(b'S'), Some(b'M')));
+                if at(x, y) == Some(b'A')
+                    && diagonal(at(x - 1, y - 1), at(x + 1, y + 1))
+                    && diagonal(at(x - 1, y + 1), at(x + 1, y - 1))
+                {
+                    part2 += 1;
+                }
+            }
+        }
+        assert_eq!(day4::try_part1(&input), Ok(part1), "seed {seed}");
+        assert_eq!(day4::try_part2(&input), Ok(part2), "seed {seed}");
+    }
+}
+
+#[test]
+fn day5() {
+    for seed in SEEDS {
+        let input = generate::puzzle_input(5, seed).unwrap();
+        let (rules, updates) = input.split_once("\n\n").unwrap();
+        let rules: HashSet<(u8, u8)> = lines(rules)
+            .map(|rule| {
+                let (before, after) = rule.split_once('|').unwrap();
+                (before.parse().unwrap(), after.parse().unwrap())
+            })
+            .collect();
+
+        let (mut part1, mut part2) = (0, 0);
+        for update in lines(updates) {
+            let mut update: Vec<u8> = update.split(',').map(|p| p.parse().unwrap()).collect();
+            let valid = (0..update.len())
+                .all(|i| (i + 1..update.len()).all(|j| !rules.contains(&(update[j], update[i]))));
+            if valid {
+                part1 += update[update.len() / 2] as usize;
+            } else {
+                update.sort_by(|&a, &b| {
+                    if rules.contains(&(a, b)) {
+                        std::cmp::Ordering::Less
+                    } else {
+                        std::cmp::Ordering::Greater
+                    }
+                });
+                part2 += update[update.len() / 2] as usize;
+            }
+        }
+        assert_eq!(day5::try_part1(&input), Ok(part1), "seed {seed}");
+        assert_eq!(day5::try_part2(&input), Ok(part2), "seed {seed}");
+    }
+}
+
+/// The cells the guard visits, or `None` if the guard walks in a loop.
+fn patrol(grid: &[Vec<u8>]) -> Option<HashSet<(isize, isize)>> {
+    let (mut x, mut y, mut direction) = grid
+        .iter()
+        .enumerate()
+        .find_map(|(y, row)| {
+            let x = row.iter().position(|c| b"^>v<".contains(c))?;
+            let direction = b"^>v<".iter().position(|c| *c == row[x])?;
+            Some((x as isize, y as isize, direction))
+        })
+        .unwrap();
+    let mut states = HashSet::new();
+    while states.insert((x, y, direction)) {
+        let (dx, dy) = [(0, -1), (1, 0), (0, 1), (-1, 0)][direction];
+        let next = usize::try_from(y + dy)
+            .ok()
+            .zip(usize::try_from(x + dx).ok())
+            .and_then(|(y, x)| grid.get(y)?.get(x));
+        match next {
+            None => return Some(states.into_iter().map(|(x, y, _)| (x, y)).collect()),
+            Some(b'#') => direction = (direction + 1) % 4,
+            Some(_) => (x, y) = (x + dx, y + dy),
+        }
+    }
+    None
+}
+
+#[test]
+fn day6() {
+    for seed in SEEDS {
+        // Smaller than the puzzle, and not square, to keep the oracle quick.
+        let input = generate::day6(&mut Rng::new(seed), 60, 45);
+        let mut grid: Vec<Vec<u8>> = lines(&input).map(|line| line.as_bytes().to_vec()).collect();
+        let visited = patrol(&grid).unwrap();
+        let mut loops = 0;
+        for &(x, y) in &visited {
+            let cell = &mut grid[y as usize][x as usize];
+            if *cell != b'.' {
+                continue;
+            }
+            *cell = b'#';
+            loops += patrol(&grid).is_none() as usize;
+            grid[y as usize][x as usize] = b'.';
+        }
+        assert_eq!(day6::try_part1(&input), Ok(visited.len()), "seed {seed}");
+        assert_eq!(day6::try_part2(&input), Ok(loops), "seed {seed}");
+    }
+}
+
+#[test]
+fn day7() {
+    for seed in SEEDS {
+        let calibration = generate::day7(&mut Rng::new(seed), 850);
+        assert_eq!(
+            day7::try_part1(&calibration.input),
+            Ok(calibration.part1),
+            "seed {seed}"
+        );
+        assert_eq!(
+            day7::try_part2(&calibration.input),
+            Ok(calibration.part2),
+            "seed {seed}"
+        );
+    }
+}
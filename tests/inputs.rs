@@ -0,0 +1,143 @@
+// The cache and guesses live in a temporary directory, which WASI has none of.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use std::cell::Cell;
+#[cfg(feature = "fetch")]
+use std::{
+    io::{Read, Write},
+    net::TcpListener,
+    sync::mpsc,
+};
+
+#[cfg(feature = "fetch")]
+use aoc2024::inputs::Http;
+use aoc2024::inputs::{Fetch, InputError, Inputs};
+use common::TempDir;
+
+fn cache(test: &str) -> TempDir {
+    common::temp_dir(&format!("inputs-{test}"))
+}
+
+/// Answers every fetch with a fixed input, counting the calls.
+#[derive(Default)]
+struct Mock {
+    fetches: Cell<usize>,
+}
+
+impl Fetch for &Mock {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, InputError> {
+        self.fetches.set(self.fetches.get() + 1);
+        Ok(format!("{year} day {day}\n"))
+    }
+}
+
+/// Answers every fetch like a server that wants a session.
+struct LoggedOut;
+
+impl Fetch for LoggedOut {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, InputError> {
+        Err(InputError::Status {
+            url: format!("{year}/day/{day}/input"),
+            status: 400,
+        })
+    }
+}
+
+#[cfg(feature = "fetch")]
+/// Serves one request with `status` and `body`, sending back the request
+/// head it received.
+fn serve_once(status: &str, body: &str) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = Vec::new();
+        let mut byte = [0];
+        while !request.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap() == 1 {
+            request.push(byte[0]);
+        }
+        stream.write_all(response.as_bytes()).unwrap();
+        sender.send(String::from_utf8(request).unwrap()).unwrap();
+    });
+    (url, receiver)
+}
+
+#[test]
+fn cached_inputs_are_not_fetched() {
+    let dir = cache("cached");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("day1.txt"), "cached\n").unwrap();
+    let mock = Mock::default();
+    let inputs = Inputs::new(&*dir, 2024, &mock);
+    assert_eq!(inputs.get(1).unwrap(), "cached\n");
+    assert_eq!(mock.fetches.get(), 0);
+}
+
+#[test]
+fn missing_inputs_are_fetched_once() {
+    let dir = cache("fetched");
+    let mock = Mock::default();
+    let inputs = Inputs::new(&*dir, 2024, &mock);
+    assert_eq!(inputs.get(5).unwrap(), "2024 day 5\n");
+    assert_eq!(inputs.get(5).unwrap(), "2024 day 5\n");
+    assert_eq!(mock.fetches.get(), 1);
+    assert_eq!(
+        std::fs::read_to_string(dir.join("day5.txt")).unwrap(),
+        "2024 day 5\n"
+    );
+}
+
+#[test]
+fn offline_inputs_can_be_missing() {
+    let dir = cache("offline");
+    let inputs = Inputs::offline(&*dir);
+    assert!(matches!(inputs.get(1), Err(InputError::Missing { .. })));
+}
+
+#[test]
+fn fetch_errors_are_not_cached() {
+    let dir = cache("fetch-error");
+    let inputs = Inputs::new(&*dir, 2024, LoggedOut);
+    assert!(matches!(
+        inputs.get(2),
+        Err(InputError::Status { status: 400, .. })
+    ));
+    assert!(!dir.join("day2.txt").exists());
+}
+
+#[cfg(feature = "fetch")]
+#[test]
+fn http_sends_the_session() {
+    let (url, request) = serve_once("200 OK", "1   2\n");
+    let dir = cache("http");
+    let inputs = Inputs::new(&*dir, 2024, Http::new(format!("{url}/"), "abc123"));
+    assert_eq!(inputs.get(3).unwrap(), "1   2\n");
+
+    let request = request.recv().unwrap().to_ascii_lowercase();
+    assert!(request.starts_with("get /2024/day/3/input "), "{request}");
+    assert!(
+        request.contains("\r\ncookie: session=abc123\r\n"),
+        "{request}"
+    );
+    assert!(dir.join("day3.txt").exists());
+}
+
+#[cfg(feature = "fetch")]
+#[test]
+fn http_errors_are_not_cached() {
+    let (url, _request) = serve_once("400 Bad Request", "Please log in.");
+    let dir = cache("http-error");
+    let inputs = Inputs::new(&*dir, 2024, Http::new(url, "expired"));
+    assert!(matches!(
+        inputs.get(2),
+        Err(InputError::Status { status: 400, .. })
+    ));
+    assert!(!dir.join("day2.txt").exists());
+}
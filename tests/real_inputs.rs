@@ -0,0 +1,93 @@
+use aoc2024::{answers::AnswerFile, *};
+
+/// `(day, part 1, part 2)` for every day in the answers file.
+fn answers() -> Vec<(u8, String, String)> {
+    let file = AnswerFile::parse(&std::fs::read_to_string(answers::PATH).unwrap()).unwrap();
+    (1..=25)
+        .filter_map(|day| {
+            let part1 = file.get(day, 1)?.to_string();
+            let part2 = file.get(day, 2).unwrap().to_string();
+            Some((day, part1, part2))
+        })
+        .collect()
+}
+
+fn input(day: u8) -> String {
+    inputs::Inputs::from_env().get(day).unwrap()
+}
+
+#[test]
+fn every_day_has_an_answer() {
+    let days: Vec<u8> = SOLUTIONS.iter().map(|solution| solution.day()).collect();
+    let answered: Vec<u8> = answers().iter().map(|&(day, _, _)| day).collect();
+    assert_eq!(days, answered);
+}
+
+#[test]
+fn registry() {
+    for (day, part1, part2) in answers() {
+        let solution = solution(day).unwrap();
+        let parsed = solution.parse(&input(day)).unwrap();
+        assert_eq!(solution.part1(&parsed).unwrap(), part1, "day {day} part 1");
+        assert_eq!(solution.part2(&parsed).unwrap(), part2, "day {day} part 2");
+        assert_eq!(
+            solution.part(3, &parsed),
+            Err(Error::Constraint("there is no part 3".to_string()))
+        );
+    }
+}
+
+#[test]
+fn free_functions() {
+    let answers = [
+        (
+            day1::part1(&input(1)).to_string(),
+            day1::part2(&input(1)).to_string(),
+        ),
+        (
+            day2::part1(&input(2)).to_string(),
+            day2::part2(&input(2)).to_string(),
+        ),
+        (
+            day3::part1(&input(3)).to_string(),
+            day3::part2(&input(3)).to_string(),
+        ),
+        (
+            day4::part1(&input(4)).to_string(),
+            day4::part2(&input(4)).to_string(),
+        ),
+        (
+            day5::part1(&input(5)).to_string(),
+            day5::part2(&input(5)).to_string(),
+        ),
+        (
+            day6::part1(&input(6)).to_string(),
+            day6::part2(&input(6)).to_string(),
+        ),
+        (
+            day7::part1(&input(7)).to_string(),
+            day7::part2(&input(7)).to_string(),
+        ),
+    ];
+    for ((part1, part2), (day, expected1, expected2)) in answers.iter().zip(self::answers()) {
+        assert_eq!(*part1, expected1, "day {day} part 1");
+        assert_eq!(*part2, expected2, "day {day} part 2");
+    }
+}
+
+#[test]
+fn day1_alternatives() {
+    let (_, part1, part2) = &answers()[0];
+    assert_eq!(&day1::part1_radix(&input(1)).to_string(), part1);
+    assert_eq!(&day1::part2_counting(&input(1)).to_string(), part2);
+}
+
+/// Tabs rule out day1's fixed width fast path, so this goes through the
+/// general parser instead.
+#[test]
+fn day1_any_width() {
+    let input = input(1).replace("   ", "\t");
+    let (_, part1, part2) = &answers()[0];
+    assert_eq!(&day1::part1(&input).to_string(), part1);
+    assert_eq!(&day1::part2(&input).to_string(), part2);
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use std::fmt::Debug;
+
+use aoc2024::*;
+use common::per_day_tests;
+
+fn check<S: Solution>()
+where
+    S::Answer1: PartialEq + Debug + Send,
+    S::Answer2: PartialEq + Debug + Send,
+{
+    let inputs = common::inputs(S::DAY, 0..8);
+    let expected: Vec<_> = inputs
+        .iter()
+        .map(|input| {
+            let parsed = S::parse(input).unwrap();
+            (S::part1(&parsed).unwrap(), S::part2(&parsed).unwrap())
+        })
+        .collect();
+
+    let sequential: Vec<_> = solve_many::<S>(inputs.iter().map(String::as_str))
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(sequential, expected, "day {}", S::DAY);
+
+    #[cfg(feature = "rayon")]
+    {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        let parallel: Vec<_> = solve_many_in::<S>(&pool, &inputs)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(parallel, expected, "day {}", S::DAY);
+    }
+}
+
+per_day_tests!(check);
+
+#[test]
+fn errors_stay_with_their_input() {
+    let results = solve_many::<day7::Day7>(["1: 1", "nonsense", "6: 2 3"]);
+    assert_eq!(results[0].as_ref().unwrap(), &(1, 1));
+    assert!(results[1].is_err());
+    assert_eq!(results[2].as_ref().unwrap(), &(6, 6));
+}
@@ -0,0 +1,388 @@
+// The cache and guesses live in a temporary directory, which WASI has none of.
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use std::{cell::RefCell, path::PathBuf, time::Duration};
+#[cfg(feature = "fetch")]
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::mpsc,
+};
+
+use aoc2024::submit::{parse_response, Hint, Outcome, Post, Submission, SubmitError, Submitter};
+use common::TempDir;
+
+/// An answer page as the site renders it.
+fn page(article: &str) -> String {
+    format!("<!DOCTYPE html>\n<html><body><main>\n<article><p>{article}</p></article>\n</main></body></html>")
+}
+
+const CORRECT: &str = "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer to finding the Chief Historian. <a href=\"/2024/day/1#part2\">[Continue to Part Two]</a>";
+const TOO_HIGH: &str = "That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data; there are also some general tips on the <a href=\"/2024/about\">about page</a>, or you can ask for hints on the <a href=\"https://www.reddit.com/r/adventofcode/\" target=\"_blank\">subreddit</a>.  Please wait one minute before trying again. <a href=\"/2024/day/1\">[Return to Day 1]</a>";
+const TOO_LOW: &str = "That's not the right answer; your answer is too low.  Please wait 5 minutes before trying again. <a href=\"/2024/day/1\">[Return to Day 1]</a>";
+const WRONG: &str = "That's not the right answer.  If you're stuck, make sure you're using the full input data.  Please wait one minute before trying again.";
+const TOO_SOON: &str = "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait. <a href=\"/2024/day/1\">[Return to Day 1]</a>";
+// A wait that fits in a `Duration`, but not after the current time.
+const TOO_SOON_FOREVER: &str = "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 18446744073709551615s left to wait.";
+// Without the wait the site asks for, so the next answer can go straight out.
+const TOO_HIGH_NO_WAIT: &str = "That's not the right answer; your answer is too high.";
+const TOO_LOW_NO_WAIT: &str = "That's not the right answer; your answer is too low.";
+const WRONG_LEVEL: &str = "You don't seem to be solving the right level.  Did you already complete it? <a href=\"/2024/day/1\">[Return to Day 1]</a>";
+
+#[test]
+fn responses() {
+    assert_eq!(parse_response(&page(CORRECT)), Outcome::Correct);
+    assert_eq!(
+        parse_response(&page(TOO_HIGH)),
+        Outcome::Incorrect {
+            hint: Some(Hint::TooHigh),
+            wait: Some(Duration::from_secs(60)),
+        }
+    );
+    assert_eq!(
+        parse_response(&page(TOO_LOW)),
+        Outcome::Incorrect {
+            hint: Some(Hint::TooLow),
+            wait: Some(Duration::from_secs(300)),
+        }
+    );
+    assert_eq!(
+        parse_response(&page(WRONG)),
+        Outcome::Incorrect {
+            hint: None,
+            wait: Some(Duration::from_secs(60)),
+        }
+    );
+    assert_eq!(
+        parse_response(&page(TOO_SOON)),
+        Outcome::TooSoon {
+            wait: Duration::from_secs(65)
+        }
+    );
+    assert_eq!(parse_response(&page(WRONG_LEVEL)), Outcome::WrongLevel);
+    assert_eq!(
+        parse_response(&page("Something <em>new</em>.")),
+        Outcome::Unrecognized("Something new.".to_string())
+    );
+}
+
+/// A path for one test's guesses file, in a directory removed along with the
+/// returned guard.
+fn guesses(test: &str) -> (TempDir, PathBuf) {
+    let dir = common::temp_dir(&format!("submit-{test}"));
+    let path = dir.join("guesses.txt");
+    (dir, path)
+}
+
+/// Answers posts with the queued pages, remembering what was posted.
+#[derive(Default)]
+struct Mock {
+    pages: RefCell<Vec<&'static str>>,
+    posted: RefCell<Vec<(u8, u8, String)>>,
+}
+
+impl Mock {
+    fn new(pages: &[&'static str]) -> Self {
+        Mock {
+            pages: RefCell::new(pages.iter().rev().copied().collect()),
+            posted: RefCell::default(),
+        }
+    }
+}
+
+impl Post for Mock {
+    fn post(&self, _: u16, day: u8, part: u8, answer: &str) -> Result<String, SubmitError> {
+        self.posted
+            .borrow_mut()
+            .push((day, part, answer.to_string()));
+        Ok(page(
+            self.pages.borrow_mut().pop().expect("unexpected post"),
+        ))
+    }
+}
+
+#[test]
+fn guesses_are_not_sent_twice() {
+    let (_dir, path) = guesses("twice");
+    let mock = Mock::new(&[TOO_HIGH_NO_WAIT, TOO_LOW_NO_WAIT, CORRECT]);
+    let mut submitter = Submitter::new(&mock, 2024, &path).unwrap();
+
+    let too_high = Outcome::Incorrect {
+        hint: Some(Hint::TooHigh),
+        wait: None,
+    };
+    assert!(matches!(
+        submitter.submit(1, 1, "100").unwrap(),
+        Submission::Posted(Outcome::Incorrect {
+            hint: Some(Hint::TooHigh),
+            ..
+        })
+    ));
+    assert_eq!(
+        submitter.submit(1, 1, "100").unwrap(),
+        Submission::Known(too_high.clone())
+    );
+    assert_eq!(
+        submitter.submit(1, 1, "150").unwrap(),
+        Submission::Known(too_high)
+    );
+
+    assert!(matches!(
+        submitter.submit(1, 1, "10").unwrap(),
+        Submission::Posted(Outcome::Incorrect {
+            hint: Some(Hint::TooLow),
+            ..
+        })
+    ));
+    assert!(matches!(
+        submitter.submit(1, 1, "5").unwrap(),
+        Submission::Known(Outcome::Incorrect {
+            hint: Some(Hint::TooLow),
+            ..
+        })
+    ));
+    assert_eq!(
+        submitter.submit(1, 1, "50").unwrap(),
+        Submission::Posted(Outcome::Correct)
+    );
+    assert_eq!(
+        submitter.submit(1, 1, "60").unwrap(),
+        Submission::Known(Outcome::WrongLevel)
+    );
+
+    let posted: Vec<_> = mock.posted.borrow().iter().map(|p| p.2.clone()).collect();
+    assert_eq!(posted, ["100", "10", "50"]);
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "1\t1\t100\ttoo high\n1\t1\t10\ttoo low\n1\t1\t50\tcorrect\n"
+    );
+
+    // A new submitter picks the guesses back up.
+    let submitter = Submitter::new(Mock::default(), 2024, &path).unwrap();
+    assert_eq!(submitter.guesses().len(), 3);
+    assert_eq!(submitter.known(1, 1, "50"), Some(Outcome::Correct));
+    assert_eq!(submitter.known(1, 2, "50"), None);
+}
+
+#[test]
+fn malformed_guesses() {
+    let (_dir, path) = guesses("malformed");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "1\t1\t100\ttoo high\n1\t2\t7\tmaybe\n").unwrap();
+    assert!(matches!(
+        Submitter::new(Mock::default(), 2024, &path),
+        Err(SubmitError::Guesses { line: 2, .. })
+    ));
+    std::fs::write(&path, "wait until\t0\nwait until\tsoon\n").unwrap();
+    assert!(matches!(
+        Submitter::new(Mock::default(), 2024, &path),
+        Err(SubmitError::Guesses { line: 2, .. })
+    ));
+    std::fs::write(&path, format!("wait until\t0\nwait until\t{}\n", u64::MAX)).unwrap();
+    assert!(matches!(
+        Submitter::new(Mock::default(), 2024, &path),
+        Err(SubmitError::Guesses { line: 2, .. })
+    ));
+}
+
+#[test]
+fn waits_too_long_to_keep() {
+    for wait in [
+        "307445734561825861m",
+        "5124095576030432h",
+        "18446744073709551615s 1s",
+    ] {
+        let article = TOO_SOON_FOREVER.replace("18446744073709551615s", wait);
+        assert!(
+            matches!(parse_response(&page(&article)), Outcome::Unrecognized(_)),
+            "{wait}"
+        );
+    }
+
+    let (_dir, path) = guesses("too-long");
+    let mut submitter = Submitter::new(Mock::new(&[TOO_SOON_FOREVER]), 2024, &path).unwrap();
+    assert!(matches!(
+        submitter.submit(1, 1, "10"),
+        Err(SubmitError::Wait(_))
+    ));
+    assert_eq!(submitter.wait_until(), None);
+}
+
+/// The wait left, if nothing was posted.
+fn too_soon(submission: Submission) -> Duration {
+    match submission {
+        Submission::Known(Outcome::TooSoon { wait }) => wait,
+        submission => panic!("expected to wait, got {submission:?}"),
+    }
+}
+
+#[test]
+fn waits_are_kept() {
+    let (_dir, path) = guesses("waits");
+    let mock = Mock::new(&[TOO_SOON]);
+    let mut submitter = Submitter::new(&mock, 2024, &path).unwrap();
+    assert_eq!(
+        submitter.submit(1, 1, "10").unwrap(),
+        Submission::Posted(Outcome::TooSoon {
+            wait: Duration::from_secs(65)
+        })
+    );
+    let wait = too_soon(submitter.submit(1, 1, "10").unwrap());
+    assert!(wait > Duration::from_secs(60) && wait <= Duration::from_secs(66));
+    assert_eq!(mock.posted.borrow().len(), 1);
+    assert!(std::fs::read_to_string(&path)
+        .unwrap()
+        .starts_with("wait until\t"));
+
+    // The wait outlives the submitter, and posting now would panic.
+    let mut submitter = Submitter::new(Mock::default(), 2024, &path).unwrap();
+    assert!(submitter.wait_until().is_some());
+    too_soon(submitter.submit(1, 2, "10").unwrap());
+}
+
+#[test]
+fn wrong_answers_start_a_wait() {
+    let (_dir, path) = guesses("wrong");
+    let mock = Mock::new(&[TOO_HIGH]);
+    let mut submitter = Submitter::new(&mock, 2024, &path).unwrap();
+    assert!(matches!(
+        submitter.submit(1, 1, "100").unwrap(),
+        Submission::Posted(Outcome::Incorrect { .. })
+    ));
+    // Earlier guesses still settle answers while waiting.
+    assert!(matches!(
+        submitter.submit(1, 1, "100").unwrap(),
+        Submission::Known(Outcome::Incorrect { .. })
+    ));
+    let wait = too_soon(submitter.submit(1, 1, "50").unwrap());
+    assert!(wait > Duration::from_secs(55) && wait <= Duration::from_secs(61));
+    assert_eq!(mock.posted.borrow().len(), 1);
+}
+
+#[test]
+fn waits_that_are_over() {
+    let (_dir, path) = guesses("over");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "wait until\t0\n").unwrap();
+    let mut submitter = Submitter::new(Mock::new(&[CORRECT]), 2024, &path).unwrap();
+    assert_eq!(
+        submitter.submit(1, 1, "50").unwrap(),
+        Submission::Posted(Outcome::Correct)
+    );
+}
+
+#[test]
+fn answers_the_file_cannot_keep() {
+    let (_dir, path) = guesses("unkeepable");
+    let mut submitter = Submitter::new(Mock::default(), 2024, &path).unwrap();
+    for answer in ["1\t2", "12\n", "12\r"] {
+        assert!(matches!(
+            submitter.submit(1, 1, answer),
+            Err(SubmitError::Answer(_))
+        ));
+    }
+    assert!(!path.exists());
+}
+
+/// Answers every post like a server that wants a session.
+struct LoggedOut;
+
+impl Post for LoggedOut {
+    fn post(&self, year: u16, day: u8, _: u8, _: &str) -> Result<String, SubmitError> {
+        Err(SubmitError::Status {
+            url: format!("{year}/day/{day}/answer"),
+            status: 400,
+        })
+    }
+}
+
+#[test]
+fn post_errors_are_not_kept() {
+    let (_dir, path) = guesses("post-error");
+    let mut submitter = Submitter::new(LoggedOut, 2024, &path).unwrap();
+    assert!(matches!(
+        submitter.submit(1, 1, "10"),
+        Err(SubmitError::Status { status: 400, .. })
+    ));
+    assert_eq!(submitter.known(1, 1, "10"), None);
+    assert!(!path.exists());
+}
+
+/// Serves one request with `page`, sending back the request head and body.
+#[cfg(feature = "fetch")]
+fn serve_once(page: String) -> (String, mpsc::Receiver<(String, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut head = String::new();
+        while !head.ends_with("\r\n\r\n") {
+            reader.read_line(&mut head).unwrap();
+        }
+        let length = head
+            .to_ascii_lowercase()
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length: ")?.parse().ok())
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        write!(
+            reader.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+            page.len()
+        )
+        .unwrap();
+        sender
+            .send((head, String::from_utf8(body).unwrap()))
+            .unwrap();
+    });
+    (url, receiver)
+}
+
+#[cfg(feature = "fetch")]
+#[test]
+fn http_posts_the_answer() {
+    let (url, request) = serve_once(page(CORRECT));
+    let (_dir, path) = guesses("http");
+    let http = aoc2024::inputs::Http::new(url, "abc123");
+    let mut submitter = Submitter::new(http, 2024, &path).unwrap();
+    assert_eq!(
+        submitter.submit(7, 2, "11387").unwrap(),
+        Submission::Posted(Outcome::Correct)
+    );
+
+    let (head, body) = request.recv().unwrap();
+    let head = head.to_ascii_lowercase();
+    assert!(head.starts_with("post /2024/day/7/answer "), "{head}");
+    assert!(head.contains("\r\ncookie: session=abc123\r\n"), "{head}");
+    assert!(
+        head.contains("\r\ncontent-type: application/x-www-form-urlencoded\r\n"),
+        "{head}"
+    );
+    assert_eq!(body, "level=2&answer=11387");
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "7\t2\t11387\tcorrect\n"
+    );
+}
+
+#[cfg(feature = "fetch")]
+#[test]
+fn http_waits_without_posting() {
+    // The stub serves a single request, so a second post would fail to connect.
+    let (url, request) = serve_once(page(TOO_SOON));
+    let (_dir, path) = guesses("http-wait");
+    let http = aoc2024::inputs::Http::new(url, "abc123");
+    let mut submitter = Submitter::new(http, 2024, &path).unwrap();
+    assert!(matches!(
+        submitter.submit(7, 2, "11387").unwrap(),
+        Submission::Posted(Outcome::TooSoon { .. })
+    ));
+    request.recv().unwrap();
+    too_soon(submitter.submit(7, 2, "11388").unwrap());
+    assert!(request.recv().is_err());
+}
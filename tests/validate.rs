@@ -0,0 +1,173 @@
+mod common;
+
+use aoc2024::{
+    generate::{self, Rng},
+    validate::{Finding, Kind},
+    *,
+};
+use common::example;
+
+fn malformed(findings: &[Finding]) -> bool {
+    findings
+        .iter()
+        .any(|finding| finding.kind == Kind::Malformed)
+}
+
+/// `(line, column, kind)` of every finding.
+fn locations(findings: &[Finding]) -> Vec<(usize, usize, Kind)> {
+    findings
+        .iter()
+        .map(|finding| (finding.line, finding.column, finding.kind))
+        .collect()
+}
+
+#[test]
+fn real_inputs_break_nothing() {
+    let inputs = inputs::Inputs::from_env();
+    for solution in SOLUTIONS {
+        let input = inputs.get(solution.day()).unwrap();
+        assert_eq!(solution.validate(&input), [], "day {}", solution.day());
+    }
+}
+
+#[test]
+fn examples_are_well_formed() {
+    for name in ["day1", "day2", "day3", "day4", "day5", "day6", "day7"] {
+        let day = name[3..].parse().unwrap();
+        let findings = solution(day).unwrap().validate(&example(name));
+        assert!(!malformed(&findings), "{name}: {findings:?}");
+    }
+}
+
+/// Replaces, deletes or inserts a few bytes of `input`.
+fn mutate(rng: &mut Rng, input: &str) -> String {
+    let mut bytes = input.as_bytes().to_vec();
+    for _ in 0..rng.range(1, 4) {
+        let i = rng.below(bytes.len() as u64 + 1) as usize;
+        let byte = *rng.pick(b"0123456789 \n|,:#.^x");
+        match rng.below(3) {
+            0 if i < bytes.len() => bytes[i] = byte,
+            1 if i < bytes.len() => {
+                bytes.remove(i);
+            }
+            _ => bytes.insert(i, byte),
+        }
+    }
+    String::from_utf8(bytes).unwrap()
+}
+
+#[test]
+fn malformed_exactly_when_parse_fails() {
+    let mut rng = Rng::new(49);
+    for solution in SOLUTIONS {
+        let day = solution.day();
+        let input = generate::puzzle_input(day, day as u64).unwrap();
+        for _ in 0..200 {
+            let input = mutate(&mut rng, &input);
+            let findings = solution.validate(&input);
+            assert_eq!(
+                malformed(&findings),
+                solution.parse(&input).is_err(),
+                "day {day}: {findings:?}\n{input}"
+            );
+        }
+    }
+}
+
+#[test]
+fn day1_reports_every_problem() {
+    let findings = day1::validate("3   4\n4 x\n1   2   3\n");
+    assert_eq!(
+        locations(&findings),
+        [
+            (1, 2, Kind::SlowPath),
+            (2, 2, Kind::SlowPath),
+            (2, 3, Kind::Malformed),
+            (3, 1, Kind::Malformed),
+            (3, 2, Kind::SlowPath),
+        ]
+    );
+}
+
+#[test]
+fn day1_reports_every_line_off_the_fixed_width() {
+    let findings = day1::validate(
+        "12345   67890
+1234   67890
+12345   67890
+12345  67890
+",
+    );
+    assert_eq!(
+        locations(&findings),
+        [(2, 5, Kind::SlowPath), (4, 8, Kind::SlowPath)]
+    );
+    assert_eq!(
+        findings[1].to_string(),
+        "line 4, column 8: slow path: line is not `NNNNN   NNNNN`, which the fixed-width path needs"
+    );
+}
+
+#[test]
+fn day2_reports_lines_like_every_other_day() {
+    let input = "7 6 4\r\n1 x 3\n\n100 2\n";
+    let findings = day2::validate(input);
+    assert_eq!(
+        locations(&findings),
+        [
+            (1, 6, Kind::Malformed),
+            (2, 3, Kind::Malformed),
+            (4, 3, Kind::Malformed),
+        ]
+    );
+    assert!(day2::parse(input).is_err());
+}
+
+#[test]
+fn day5_conflicting_rules() {
+    let input = "11|22\n22|33\n33|11\n\n11,22,33\n33,22,11\n";
+    let findings = day5::validate(input);
+    assert_eq!(
+        locations(&findings),
+        [(5, 1, Kind::Unsolvable), (6, 1, Kind::Unsolvable)]
+    );
+}
+
+#[test]
+fn day5_cycle_in_order() {
+    let findings = day5::validate("12|34\n34|12\n\n12,34\n");
+    assert_eq!(locations(&findings), [(4, 1, Kind::Unsolvable)]);
+}
+
+#[test]
+fn day5_bad_pages() {
+    let findings = day5::validate("11|2\n1x|22\n\n11,22,100\n");
+    assert_eq!(
+        locations(&findings),
+        [
+            (1, 4, Kind::Malformed),
+            (2, 1, Kind::Malformed),
+            (4, 7, Kind::Malformed),
+        ]
+    );
+}
+
+#[test]
+fn day6_guard_stuck_in_a_loop() {
+    let input = ".#...\n....#\n#....\n.^.#.\n.....\n";
+    let findings = day6::validate(input);
+    assert_eq!(locations(&findings), [(4, 2, Kind::Unsolvable)]);
+}
+
+// There is no SIMD path to give up on wasm32.
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn day7_long_lines_take_the_slow_path() {
+    let long = format!("1: {}\n", ["1"; 31].join(" "));
+    let input = format!("3: 1 2\n{long}{}", "190: 10 19\n".repeat(10));
+    let findings = day7::validate(&input);
+    assert!(matches!(
+        locations(&findings)[..],
+        [(2, 64, Kind::SlowPath), ..]
+    ));
+}
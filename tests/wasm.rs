@@ -0,0 +1,44 @@
+use aoc2024::{
+    answers::{self, AnswerFile},
+    inputs::Inputs,
+    wasm::solve,
+    SOLUTIONS,
+};
+
+#[test]
+fn solves_every_part() {
+    let file = AnswerFile::parse(&std::fs::read_to_string(answers::PATH).unwrap()).unwrap();
+    let inputs = Inputs::from_env();
+    for solution in SOLUTIONS {
+        let day = solution.day();
+        let input = inputs.get(day).unwrap();
+        for part in [1, 2] {
+            assert_eq!(
+                solve(day, part, &input).as_deref(),
+                Ok(file.get(day, part).unwrap()),
+                "day {day} part {part}"
+            );
+        }
+    }
+}
+
+#[test]
+fn errors() {
+    assert_eq!(solve(26, 1, ""), Err("there is no day 26".to_string()));
+    assert_eq!(
+        solve(1, 3, "3   4\n"),
+        Err("there is no part 3".to_string())
+    );
+    assert_eq!(
+        solve(1, 1, "3   4\n4   x\n"),
+        Err("parse error: line 2, column 5: expected location id".to_string())
+    );
+    assert_eq!(
+        solve(5, 1, &format!("11|22\n\n{}\n", ["11"; 24].join(","))),
+        Err("invalid input: line 3: update has more than 23 pages".to_string())
+    );
+    assert_eq!(
+        solve(1, 1, "9223372036854775807 -9223372036854775808\n"),
+        Err("no answer: total distance 18446744073709551615 overflows".to_string())
+    );
+}